@@ -1,6 +1,8 @@
+use std::time::{Duration, SystemTime};
+
 use crate::{
-  components::home::Mode,
-  systemd::{UnitId, UnitWithStatus},
+  components::home::{BatchOperation, LogGrep, LogLine, LogRange, MenuItem, Mode},
+  systemd::{Scope, ServiceEnvironment, SocketInfo, StateFilter, TimerInfo, UnitId, UnitScope, UnitWithStatus},
 };
 
 #[derive(Debug, Clone)]
@@ -15,20 +17,104 @@ pub enum Action {
   ToggleShowLogger,
   RefreshServices,
   SetServices(Vec<UnitWithStatus>),
+  // Every unit in these scopes, from a refresh. Units in them that aren't in `units` have gone away
+  SyncServices { scopes: Vec<UnitScope>, units: Vec<UnitWithStatus> },
+  // Every unit has been fetched at startup
+  UnitsLoaded,
+  UnitsLoadFailed(String),
+  // Ask "Are you sure?" before running an action
+  ConfirmAction(Box<MenuItem>),
+  // The fetch started by `RefreshServices` is done, whether it worked or not
+  RefreshServicesFinished,
   EnterMode(Mode),
   EnterError(String),
+  OfferPrivilegedRetry(Vec<String>),
+  // The last service action failed, let the user try it again from the error popup
+  OfferRetry,
+  RunPrivileged(Vec<String>),
+  // Run `journalctl` with these args in the terminal, so the user's pager is used
+  OpenJournalInPager(Vec<String>),
   CancelTask,
   ToggleHelp,
   SetUnitFilePath { unit: UnitId, path: Result<String, String> },
   CopyUnitFilePath,
-  SetLogs { unit: UnitId, logs: Vec<String> },
-  AppendLogLine { unit: UnitId, line: String },
+  CopyUnitName,
+  CopyInvocationId,
+  OpenUnitFileDirectory,
+  ViewUnitFile,
+  ShowDependencies,
+  ShowTimers,
+  SetTimers(Vec<TimerInfo>),
+  SetDependencies { unit: UnitId, dependencies: Vec<(String, Vec<String>)> },
+  JumpToUnit(UnitId),
+  CopyDetails,
+  CopyLogs,
+  ExportLogs,
+  ToggleLogPreviews,
+  ToggleGroupByType,
+  ToggleHideInactive,
+  ToggleHideTransient,
+  ToggleWatch(UnitId),
+  ToggleLogColors,
+  ToggleRelativeLogTimes,
+  ToggleLogsPaused,
+  ToggleStructuredLogs,
+  SetLogGrep(Option<LogGrep>),
+  CycleSortMode,
+  SetStateFilter(Option<StateFilter>),
+  SetScope(Scope),
+  SetLogLines(usize),
+  SetLogPriority(Option<u8>),
+  SetLogBoot(Option<i32>),
+  SetLogRange(LogRange),
+  // journalctl `--since`/`--until` values
+  SetLogTimeRange { since: Option<String>, until: Option<String> },
+  SetLogPreview { unit: UnitId, line: String },
+  SetTimerTimes { unit: UnitId, next_elapse: Option<SystemTime>, last_trigger: Option<SystemTime> },
+  SetStateChangeTimes { unit: UnitId, active_enter: Option<SystemTime>, inactive_enter: Option<SystemTime> },
+  SetMainPid { unit: UnitId, pid: u32 },
+  SetEnablementState { unit: UnitId, state: String },
+  // Forget cached unit file paths and enablement states, for one unit or (`None`) all of them
+  InvalidateUnitCache(Option<UnitId>),
+  SetSocketInfo { unit: UnitId, info: SocketInfo },
+  RefreshDetails,
+  SetRestartCount { unit: UnitId, n_restarts: u32 },
+  SetResourceUsage { unit: UnitId, memory_current: Option<u64>, cpu_usage: Option<Duration> },
+  SetLogs { unit: UnitId, logs: Vec<LogLine> },
+  AppendLogLine { unit: UnitId, line: LogLine },
   StartService(UnitId),
   StopService(UnitId),
   RestartService(UnitId),
+  // Run an operation on each of these units in turn
+  RunBatch(BatchOperation, Vec<UnitId>),
+  // (units done, total) for the batch operation that's running
+  SetBatchProgress(usize, usize),
+  // What the unit being acted on is doing right now, e.g. `activating (start-pre)`
+  SetProcessingStatus(String),
   ReloadService(UnitId),
+  DaemonReload,
+  // The unit's file or a drop-in was just changed in an editor
+  UnitFileEdited(UnitId),
+  // daemon-reload, then restart the unit, so an edit takes effect
+  ApplyUnitChanges(UnitId),
+  ForceStopService(UnitId),
+  OpenSignalMenu(UnitId),
+  KillService { unit: UnitId, signal: i32 },
   EnableService(UnitId),
   DisableService(UnitId),
+  MaskService(UnitId),
+  ResetFailed(UnitId),
+  FreezeService(UnitId),
+  ThawService(UnitId),
+  SetDropInPaths { unit: UnitId, paths: Vec<String> },
+  SetTransient { unit: UnitId, transient: bool },
+  SetEnvironment { unit: UnitId, environment: ServiceEnvironment },
+  ShowEnvironment,
+  ShowAllProperties,
+  // Show some text in the unit file popup, e.g. a service's environment
+  ViewText { title: String, contents: String },
+  SetFreezerState { unit: UnitId, state: String },
+  UnmaskService(UnitId),
   ScrollUp(u16),
   ScrollDown(u16),
   ScrollToTop,
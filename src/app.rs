@@ -1,6 +1,6 @@
-use std::{process::Command, sync::Arc};
+use std::{process::Command, sync::Arc, time::Duration};
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use log::error;
 use tokio::sync::{mpsc, Mutex};
 use tracing::debug;
@@ -8,27 +8,63 @@ use tracing::debug;
 use crate::{
   action::Action,
   components::{
-    home::{Home, Mode},
+    home::{Home, Mode, StartupUnit},
     Component,
   },
+  config::Config,
   event::EventHandler,
-  systemd::{get_all_services, Scope},
+  state::State,
+  systemd::{self, get_all_services, get_all_services_incrementally, Scope},
   terminal::TerminalHandler,
+  utils::find_editor,
 };
 
 pub struct App {
   pub scope: Scope,
   pub home: Arc<Mutex<Home>>,
   pub limit_units: Vec<String>,
+  pub incremental_load: bool,
+  /// How often to refresh unit statuses. Zero disables auto-refresh
+  pub refresh_interval: Duration,
+  pub mouse_capture: bool,
   pub should_quit: bool,
   pub should_suspend: bool,
 }
 
 impl App {
-  pub fn new(scope: Scope, limit_units: Vec<String>) -> Result<Self> {
-    let home = Home::new(scope, &limit_units);
+  pub fn new(
+    scope: Scope,
+    limit_units: Vec<String>,
+    config: Config,
+    startup_unit: Option<StartupUnit>,
+  ) -> Result<Self> {
+    let incremental_load = config.incremental_load;
+    let refresh_interval = Duration::from_millis(config.refresh_interval);
+    let mouse_capture = config.mouse_capture;
+    let mut home = Home::new(scope, &limit_units, config);
+    let state = State::load();
+    // an explicit --select beats whatever was selected last time
+    if startup_unit.is_none() {
+      home.restore_selection = state.last_selected;
+    }
+    home.startup_unit = startup_unit;
+    if let Some(show_logger) = state.show_logger {
+      home.show_logger = show_logger;
+    }
+    if let Some(logger_percent) = state.logger_percent {
+      home.set_logger_percent(logger_percent);
+    }
     let home = Arc::new(Mutex::new(home));
-    Ok(Self { scope, home, limit_units, should_quit: false, should_suspend: false })
+    Ok(Self {
+      scope,
+      home,
+      limit_units,
+      incremental_load,
+      refresh_interval,
+      mouse_capture,
+      should_quit: false,
+      should_suspend: false,
+    })
   }
 
   pub async fn run(&mut self) -> Result<()> {
@@ -62,13 +98,32 @@ impl App {
 
     self.home.lock().await.init(action_tx.clone())?;
 
-    let units = get_all_services(self.scope, &self.limit_units)
-      .await
-      .context("Unable to get services. Check that systemd is running and try running this tool with sudo.")?;
-    self.home.lock().await.set_units(units);
+    // load in the background so the TUI comes up (and can be quit) right away, however slow systemd is
+    self.home.lock().await.units_loading = true;
+    let tx = action_tx.clone();
+    let scope = self.scope;
+    let limit_units = self.limit_units.clone();
+    let incremental_load = self.incremental_load;
+    tokio::spawn(async move {
+      let result = if incremental_load {
+        get_all_services_incrementally(scope, &limit_units, |units| {
+          let _ = tx.send(Action::SetServices(units));
+        })
+        .await
+      } else {
+        get_all_services(scope, &limit_units).await.map(|units| {
+          let _ = tx.send(Action::SetServices(units));
+        })
+      };
+      // failures (usually D-Bus connection trouble) go in the TUI's error popup instead of a backtrace
+      let _ = match result {
+        Ok(()) => tx.send(Action::UnitsLoaded),
+        Err(e) => tx.send(Action::UnitsLoadFailed(format!("Unable to get services: {:#}", e))),
+      };
+    });
 
-    let mut terminal = TerminalHandler::new(self.home.clone());
-    let mut event = EventHandler::new(self.home.clone(), action_tx.clone());
+    let mut terminal = TerminalHandler::new(self.home.clone(), self.mouse_capture);
+    let mut event = EventHandler::new(self.home.clone(), action_tx.clone(), self.refresh_interval);
 
     terminal.render().await;
 
@@ -78,6 +133,7 @@ impl App {
           // these are too big to log in full
           Action::SetLogs { .. } => debug!("action: SetLogs"),
           Action::SetServices { .. } => debug!("action: SetServices"),
+          Action::SyncServices { .. } => debug!("action: SyncServices"),
           _ => debug!("action: {:?}", action),
         }
 
@@ -96,6 +152,21 @@ impl App {
           Action::Resize(_, _) => terminal.render().await,
           // This would normally be in home.rs, but it needs to do some terminal and event handling stuff that's easier here
           Action::EditUnitFile { unit, path } => {
+            if let Some(host) = systemd::remote_host() {
+              action_tx.send(Action::EnterError(format!(
+                "Editing files on {} isn't supported yet. Edit {} over `ssh {}` instead",
+                host, path, host
+              )))?;
+              continue;
+            }
+            // check before leaving the TUI, so a missing editor is an error popup instead of a confusing failed spawn
+            let Some((editor, editor_args)) = find_editor() else {
+              action_tx.send(Action::EnterError(
+                "No editor found. Set $VISUAL or $EDITOR, or install nano or vi".to_string(),
+              ))?;
+              continue;
+            };
+
             event.stop();
             let mut tui = terminal.tui.lock().await;
             tui.exit()?;
@@ -109,28 +180,72 @@ impl App {
             };
 
             let unit_file_contents = read_unit_file_contents();
-            let editor = std::env::var("EDITOR").unwrap_or_else(|_| "nano".to_string());
-            match Command::new(&editor).arg(&path).status() {
+            match Command::new(&editor).args(&editor_args).arg(&path).status() {
               Ok(_) => {
                 tui.enter()?;
                 tui.clear()?;
-                event = EventHandler::new(self.home.clone(), action_tx.clone());
+                event = EventHandler::new(self.home.clone(), action_tx.clone(), self.refresh_interval);
 
                 let new_unit_file_contents = read_unit_file_contents();
                 if unit_file_contents != new_unit_file_contents {
-                  action_tx.send(Action::ReloadService(unit))?;
+                  // offer to apply the changes right away, the menu puts that first
+                  action_tx.send(Action::InvalidateUnitCache(Some(unit.clone())))?;
+                  action_tx.send(Action::UnitFileEdited(unit))?;
+                  action_tx.send(Action::EnterMode(Mode::ActionMenu))?;
+                } else {
+                  action_tx.send(Action::EnterMode(Mode::ServiceList))?;
                 }
-
-                action_tx.send(Action::EnterMode(Mode::ServiceList))?;
               },
               Err(e) => {
                 tui.enter()?;
                 tui.clear()?;
-                event = EventHandler::new(self.home.clone(), action_tx.clone());
+                event = EventHandler::new(self.home.clone(), action_tx.clone(), self.refresh_interval);
                 action_tx.send(Action::EnterError(format!("Failed to open editor `{}`: {}", editor, e)))?;
               },
             }
           },
+          // Like editing, hand the terminal over to journalctl (and its pager) until the user quits it
+          Action::OpenJournalInPager(journalctl_args) => {
+            event.stop();
+            let mut tui = terminal.tui.lock().await;
+            tui.exit()?;
+
+            let status = systemd::interactive_host_command("journalctl", &journalctl_args).status();
+
+            tui.enter()?;
+            tui.clear()?;
+            event = EventHandler::new(self.home.clone(), action_tx.clone(), self.refresh_interval);
+
+            match status {
+              Ok(_) => action_tx.send(Action::Render)?,
+              Err(e) => action_tx.send(Action::EnterError(format!("Failed to run journalctl: {}", e)))?,
+            }
+          },
+          // Retry a service action as root with pkexec (sudo with --host). Like editing, this needs the terminal in case polkit prompts there
+          Action::RunPrivileged(systemctl_args) => {
+            event.stop();
+            let mut tui = terminal.tui.lock().await;
+            tui.exit()?;
+
+            let elevate = systemd::elevate_command();
+            let command_string = format!("{} systemctl {}", elevate, systemctl_args.join(" "));
+            eprintln!("Running `{}`...", command_string);
+            let args = std::iter::once("systemctl").chain(systemctl_args.iter().map(String::as_str));
+            let status = systemd::interactive_host_command(elevate, args).status();
+
+            tui.enter()?;
+            tui.clear()?;
+            event = EventHandler::new(self.home.clone(), action_tx.clone(), self.refresh_interval);
+
+            match status {
+              Ok(status) if status.success() => {
+                action_tx.send(Action::RefreshServices)?;
+                action_tx.send(Action::EnterMode(Mode::ServiceList))?;
+              },
+              Ok(status) => action_tx.send(Action::EnterError(format!("`{}` failed ({})", command_string, status)))?,
+              Err(e) => action_tx.send(Action::EnterError(format!("Failed to run `{}`: {}", command_string, e)))?,
+            }
+          },
           _ => {
             if let Some(_action) = self.home.lock().await.dispatch(action) {
               action_tx.send(_action)?
@@ -143,11 +258,21 @@ impl App {
         event.stop();
         terminal.task.await?;
         event.task.await?;
-        terminal = TerminalHandler::new(self.home.clone());
-        event = EventHandler::new(self.home.clone(), action_tx.clone());
+        terminal = TerminalHandler::new(self.home.clone(), self.mouse_capture);
+        event = EventHandler::new(self.home.clone(), action_tx.clone(), self.refresh_interval);
         action_tx.send(Action::Resume)?;
         action_tx.send(Action::Render)?;
       } else if self.should_quit {
+        let home = self.home.lock().await;
+        let state = State {
+          last_selected: home.selected_service(),
+          show_logger: Some(home.show_logger),
+          logger_percent: Some(home.logger_percent),
+        };
+        drop(home);
+        if let Err(e) = state.save() {
+          error!("Failed to save state: {}", e);
+        }
         terminal.stop()?;
         event.stop();
         terminal.task.await?;
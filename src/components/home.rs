@@ -1,9 +1,11 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use futures::Future;
+use anyhow::Context;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use futures::{Future, StreamExt};
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use ratatui::{
-  layout::{Constraint, Direction, Layout, Rect},
+  layout::{Constraint, Direction, Layout, Margin, Position, Rect},
   style::{Color, Modifier, Style},
   text::{Line, Span},
   widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
@@ -13,21 +15,65 @@ use tokio::{
   sync::mpsc::{self, UnboundedSender},
   task::JoinHandle,
 };
+use tokio_stream::wrappers::UnboundedReceiverStream;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 use tui_input::{backend::crossterm::EventHandler, Input};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use std::{
-  process::{Command, Stdio},
-  time::Duration,
+  cmp::Reverse,
+  collections::{HashMap, HashSet},
+  io::Write,
+  ops::Deref,
+  path::{Path, PathBuf},
+  process::Stdio,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+  },
+  time::{Duration, SystemTime},
 };
 
 use super::{logger::Logger, Component, Frame};
 use crate::{
   action::Action,
-  systemd::{self, Scope, UnitId, UnitScope, UnitWithStatus},
+  config::{Colors, Config},
+  systemd::{self, Scope, ServiceEnvironment, StateFilter, TimerInfo, UnitId, UnitScope, UnitWithStatus},
+  utils::{format_bytes, format_duration, format_relative_time, get_data_dir},
 };
 
+// How far ctrl+j/ctrl+k and {/} move the selection in the services list
+const LIST_JUMP_SIZE: usize = 10;
+
+// The logger pane's share of the screen height, and how much ctrl+↑/ctrl+↓ change it by
+const DEFAULT_LOGGER_PERCENT: u16 = 50;
+const LOGGER_PERCENT_STEP: u16 = 10;
+const MIN_LOGGER_PERCENT: u16 = 10;
+const MAX_LOGGER_PERCENT: u16 = 90;
+
+// How many journalctl processes can run at once to fetch log previews for the services list
+const LOG_PREVIEW_CONCURRENCY: usize = 4;
+// bounds for how many log lines we fetch, so a typo can't ask journalctl for the entire journal
+const MIN_LOG_LINES: usize = 10;
+const MAX_LOG_LINES: usize = 100_000;
+// some clipboard managers choke on huge payloads, only copy the most recent logs beyond this
+const MAX_CLIPBOARD_LOG_BYTES: usize = 1024 * 1024;
+// syslog priority names, indexed by level (0 is the most severe)
+const LOG_PRIORITIES: [&str; 8] = ["emerg", "alert", "crit", "err", "warning", "notice", "info", "debug"];
+// services restarted at least this many times get a marker in the services list
+const FLAPPING_RESTART_COUNT: u32 = 3;
+const SPINNER_CHARS: &[char] = &['⣷', '⣯', '⣟', '⡿', '⢿', '⣻', '⣽', '⣾'];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(200);
+// How often to check on the unit while a service action is running
+const PROCESSING_STATUS_INTERVAL: Duration = Duration::from_millis(500);
+// How long to wait for the selection to settle before fetching logs, unless `log_debounce` is set. Until we know how
+// long journalctl takes it's the default, after that it's half that time, within the bounds
+const DEFAULT_LOG_DEBOUNCE: Duration = Duration::from_millis(100);
+const MIN_LOG_DEBOUNCE: Duration = Duration::from_millis(10);
+const MAX_LOG_DEBOUNCE: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub enum Mode {
   #[default]
@@ -37,29 +83,377 @@ pub enum Mode {
   ActionMenu,
   Processing,
   Error,
+  Confirm,
+  LogSearch,
+  LogGrep,
+  /// Moving a cursor over the log lines
+  LogLines,
+  Success,
+  UnitFile,
+  Dependencies,
+  Timers,
+  LogTimeRange,
+}
+
+/// How the services list is ordered
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum SortMode {
+  #[default]
+  Name,
+  ActiveState,
+  FailedFirst,
+}
+
+impl SortMode {
+  pub fn next(self) -> Self {
+    match self {
+      SortMode::Name => SortMode::ActiveState,
+      SortMode::ActiveState => SortMode::FailedFirst,
+      SortMode::FailedFirst => SortMode::Name,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      SortMode::Name => "by name",
+      SortMode::ActiveState => "by status",
+      SortMode::FailedFirst => "failed first",
+    }
+  }
+
+  // lower sorts first. Units are already in alphabetical order, so Name doesn't need a key
+  fn rank(&self, unit: &UnitWithStatus) -> u8 {
+    match self {
+      SortMode::Name => 0,
+      SortMode::ActiveState => match unit.activation_state.as_str() {
+        "failed" => 0,
+        "active" => 1,
+        "activating" | "deactivating" | "reloading" => 2,
+        "inactive" => 3,
+        _ => 4,
+      },
+      SortMode::FailedFirst => !unit.is_failed() as u8,
+    }
+  }
+}
+
+/// Operations that can be run on all the units marked with `v` at once. Also what `--action` can offer to do
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum BatchOperation {
+  Start,
+  Stop,
+  Restart,
+}
+
+impl BatchOperation {
+  pub fn name(&self) -> &'static str {
+    match self {
+      BatchOperation::Start => "Start",
+      BatchOperation::Stop => "Stop",
+      BatchOperation::Restart => "Restart",
+    }
+  }
+
+  fn systemctl_verb(&self) -> &'static str {
+    match self {
+      BatchOperation::Start => "start",
+      BatchOperation::Stop => "stop",
+      BatchOperation::Restart => "restart",
+    }
+  }
+
+  // The same operation on just one unit
+  fn unit_action(&self, unit: UnitId) -> Action {
+    match self {
+      BatchOperation::Start => Action::StartService(unit),
+      BatchOperation::Stop => Action::StopService(unit),
+      BatchOperation::Restart => Action::RestartService(unit),
+    }
+  }
+}
+
+/// The unit to select once units are loaded (`--select`), and an action to ask about running on it (`--action`)
+#[derive(Debug, Clone)]
+pub struct StartupUnit {
+  pub name: String,
+  pub action: Option<BatchOperation>,
+}
+
+/// How much of a unit's logs to fetch
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum LogRange {
+  /// The last `log_lines` lines
+  #[default]
+  Tail,
+  /// Everything since the current boot (or the boot picked with b/B)
+  Boot,
+  /// Everything in the journal
+  All,
+}
+
+impl LogRange {
+  pub fn next(self) -> Self {
+    match self {
+      LogRange::Tail => LogRange::Boot,
+      LogRange::Boot => LogRange::All,
+      LogRange::All => LogRange::Tail,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      LogRange::Tail => "tail",
+      LogRange::Boot => "since boot",
+      LogRange::All => "all",
+    }
+  }
+}
+
+/// A watched unit changed state. Shown in the status line until the next key press
+#[derive(Debug, Clone)]
+pub struct WatchAlert {
+  pub time: chrono::DateTime<chrono::Local>,
+  pub message: String,
+  pub color: Color,
+}
+
+/// A filter on the logs pane: lines that don't match are hidden, including ones that come in while following
+#[derive(Debug, Clone)]
+pub enum LogGrep {
+  /// Case-insensitive, like the log search
+  Text(String),
+  Regex(regex::Regex),
+}
+
+impl LogGrep {
+  pub fn new(pattern: &str, regex: bool) -> Result<Self, regex::Error> {
+    Ok(if regex { LogGrep::Regex(regex::Regex::new(pattern)?) } else { LogGrep::Text(pattern.to_string()) })
+  }
+
+  pub fn is_match(&self, line: &str) -> bool {
+    match self {
+      LogGrep::Text(text) => !find_matches(line, text).is_empty(),
+      LogGrep::Regex(regex) => regex.is_match(line),
+    }
+  }
+
+  pub fn pattern(&self) -> &str {
+    match self {
+      LogGrep::Text(text) => text,
+      LogGrep::Regex(regex) => regex.as_str(),
+    }
+  }
+}
+
+/// A line of a unit's logs
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+  pub text: String,
+  /// The syslog priority (0 is emerg, 7 is debug). Only known for structured logs
+  pub priority: Option<u8>,
+}
+
+impl LogLine {
+  pub fn plain(text: impl Into<String>) -> Self {
+    Self { text: text.into(), priority: None }
+  }
+
+  /// Parse a journal entry from `journalctl --output=json` into `<time> <identifier>[<pid>]: <message>`, with the
+  /// identifiers padded so messages line up. Anything that doesn't parse is kept as-is
+  pub fn from_json(line: &str) -> Self {
+    let Ok(entry) = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(line) else {
+      return Self::plain(line);
+    };
+    let field = |name: &str| entry.get(name).and_then(|v| v.as_str());
+    let message = match entry.get("MESSAGE") {
+      Some(serde_json::Value::String(message)) => message.clone(),
+      // messages that aren't valid UTF-8 come as an array of bytes
+      Some(serde_json::Value::Array(bytes)) => {
+        String::from_utf8_lossy(&bytes.iter().filter_map(|b| b.as_u64()).map(|b| b as u8).collect_vec()).into_owned()
+      },
+      _ => return Self::plain(line),
+    };
+    let Some(micros) = field("__REALTIME_TIMESTAMP").and_then(|t| t.parse::<u64>().ok()) else {
+      return Self::plain(line);
+    };
+    let time = chrono::DateTime::<chrono::Local>::from(SystemTime::UNIX_EPOCH + Duration::from_micros(micros));
+    // same format as short-iso, so relative times still work
+    let time = time.format("%Y-%m-%dT%H:%M:%S%z");
+    let source = match (field("SYSLOG_IDENTIFIER").or(field("_COMM")), field("_PID")) {
+      (Some(identifier), Some(pid)) => format!("{}[{}]:", identifier, pid),
+      (Some(identifier), None) => format!("{}:", identifier),
+      _ => String::new(),
+    };
+
+    Self {
+      text: format!("{} {:<24} {}", time, source, message),
+      priority: field("PRIORITY").and_then(|p| p.parse().ok()),
+    }
+  }
+}
+
+impl Deref for LogLine {
+  type Target = str;
+
+  fn deref(&self) -> &str {
+    &self.text
+  }
+}
+
+/// Sent to the log loading thread when the selection (or what we want to see of its logs) changes
+#[derive(Debug, Clone)]
+pub struct LogRequest {
+  pub unit: UnitId,
+  pub lines: usize,
+  pub range: LogRange,
+  pub priority: Option<u8>,
+  pub boot: Option<i32>,
+  /// journalctl `--since`/`--until` values. If either is set, logs aren't followed
+  pub since: Option<String>,
+  pub until: Option<String>,
+  /// Fetch the logs as JSON and parse them, instead of journalctl's own formatting
+  pub structured: bool,
+  /// Whether the unit is active, i.e. whether it's worth asking systemd about its resource usage
+  pub active: bool,
+  /// The unit file path is already known from an earlier selection, no need to look it up again
+  pub file_path_cached: bool,
+  /// Same for the enablement state
+  pub enablement_state_cached: bool,
 }
 
 #[derive(Default)]
 pub struct Home {
   pub scope: Scope,
   pub limit_units: Vec<String>,
+  pub config: Config,
   pub logger: Logger,
   pub show_logger: bool,
+  /// How much of the screen's height the logger pane takes up, in percent
+  pub logger_percent: u16,
   pub all_units: IndexMap<UnitId, UnitWithStatus>,
-  pub filtered_units: StatefulList<UnitWithStatus>,
-  pub logs: Vec<String>,
+  pub state_filter: Option<StateFilter>,
+  pub sort_mode: SortMode,
+  /// Indices into `all_units`, so filtering doesn't have to clone every matching unit
+  pub filtered_units: StatefulList<usize>,
+  /// Char indices of each filtered unit's short name that matched the search
+  pub search_match_indices: HashMap<usize, Vec<usize>>,
+  pub logs: Vec<LogLine>,
   pub logs_scroll_offset: u16,
+  /// Index of the log line under the cursor in `LogLines` mode, counting from the top (newest) line shown
+  pub log_cursor: usize,
+  /// Contents of the unit file being viewed in the unit file popup
+  pub unit_file_contents: String,
+  /// Title for the unit file popup when it's showing something other than the unit file
+  pub unit_file_title: Option<String>,
+  /// Only lines of the unit file popup containing this are shown. Typed after `/`
+  pub unit_file_filter: Input,
+  pub editing_unit_file_filter: bool,
+  /// Where to go when the unit file popup is closed
+  pub unit_file_return_mode: Mode,
+  pub unit_file_scroll_offset: u16,
+  pub help_scroll_offset: u16,
+  /// Dependencies of `dependencies_of`, shown in the dependencies popup
+  pub dependencies: StatefulList<DependencyRow>,
+  pub dependencies_of: Option<UnitId>,
+  pub timers: StatefulList<TimerInfo>,
+  /// The unit that was selected last time, to select once it's loaded (unless the user has moved on by then)
+  pub restore_selection: Option<UnitId>,
+  // where things were last rendered, for mouse handling
+  pub services_list_area: Rect,
+  pub logs_area: Rect,
+  pub action_menu_area: Rect,
+  /// How many lines of logs to fetch for the selected unit
+  pub log_lines: usize,
+  /// Only show logs at this syslog priority level or more severe
+  pub log_priority: Option<u8>,
+  /// Only show logs from this boot: 0 is the current boot, -1 the one before, etc. None means all boots
+  pub log_boot: Option<i32>,
+  pub log_range: LogRange,
+  /// Only show logs after/before these times, in any format journalctl understands (e.g. `1 hour ago`)
+  pub log_since: Option<String>,
+  pub log_until: Option<String>,
+  pub log_since_input: Input,
+  pub log_until_input: Input,
+  /// Whether the until field has focus in the time range popup (otherwise since does)
+  pub editing_log_until: bool,
+  /// The selected unit's logs have been requested and haven't shown up yet
+  pub logs_loading: bool,
   pub mode: Mode,
   pub previous_mode: Option<Mode>,
   pub input: Input,
+  /// Text to find in the logs pane; matches are highlighted and can be jumped between with n/N
+  pub log_search_input: Input,
+  /// Only log lines matching this are shown. Set with `|` or `--log-grep`
+  pub log_grep: Option<LogGrep>,
+  pub log_grep_input: Input,
+  /// Whether the grep prompt's text is a regex. Toggled with Tab in the prompt
+  pub log_grep_regex: bool,
   pub menu_items: StatefulList<MenuItem>,
+  pub pending_action: Option<MenuItem>,
+  /// Where to go if the pending action isn't confirmed
+  pub confirm_return_mode: Mode,
+  /// Units marked with `v`. The action menu's start/stop/restart apply to all of them while any are marked
+  pub marked_units: HashSet<UnitId>,
+  /// The initial load of units hasn't finished yet
+  pub units_loading: bool,
+  /// From `--select`, handled once all units have loaded
+  pub startup_unit: Option<StartupUnit>,
+  /// Units watched with `W`: a change in their active state is announced in the status line
+  pub watched_units: HashSet<UnitId>,
+  pub watch_alert: Option<WatchAlert>,
+  /// (units done, total) while a batch operation is running
+  pub batch_progress: Option<(usize, usize)>,
+  /// The state of the unit a service action is running on, shown under the spinner
+  pub processing_status: Option<String>,
+  /// Units whose files have been edited since they were last restarted from here
+  pub edited_units: HashSet<UnitId>,
   pub cancel_token: Option<CancellationToken>,
+  /// A `RefreshServices` fetch is running
+  pub refreshing_services: bool,
+  /// Another `RefreshServices` came in while one was running, do one more when it's done
+  pub refresh_queued: bool,
+  /// `systemctl` args for retrying a failed action with pkexec, if that's an option
+  pub privileged_retry: Option<Vec<String>>,
+  /// The most recent action that ran a systemd operation
+  pub last_service_action: Option<Action>,
+  /// The action to retry from the error popup, if it was a failed service action
+  pub retry_action: Option<Action>,
+  /// Running as root. Otherwise, changing system units needs polkit to allow it
+  pub is_root: bool,
   pub spinner_tick: u8,
   pub error_message: String,
+  pub success_message: String,
   pub action_tx: Option<mpsc::UnboundedSender<Action>>,
-  pub journalctl_tx: Option<std::sync::mpsc::Sender<UnitId>>,
+  pub journalctl_tx: Option<std::sync::mpsc::Sender<LogRequest>>,
+  /// Show the last log line of each visible unit in the services list
+  pub show_log_previews: bool,
+  pub log_previews: HashMap<UnitId, String>,
+  pub log_previews_requested: HashSet<UnitId>,
+  pub log_preview_tx: Option<UnboundedSender<UnitId>>,
+  /// Hold new log lines back so the logs pane stays put while reading. They show up when unpaused
+  pub logs_paused: bool,
+  pub paused_log_lines: Vec<LogLine>,
+  /// Color log lines by their detected level
+  pub colorize_logs: bool,
+  /// Fetch logs as JSON, see `LogRequest::structured`
+  pub structured_logs: bool,
+  /// Show log timestamps as e.g. `3m 2s ago` instead of the absolute time
+  pub relative_log_times: bool,
+  /// Leave inactive (dead) units out of the list
+  pub hide_inactive: bool,
+  /// Leave transient units (made by `systemd-run`, scopes, etc.) out of the list
+  pub hide_transient: bool,
+  /// `g` was just pressed in the services list, another `g` goes to the top
+  pub pending_g: bool,
+  /// Group the services list under a header for each unit type
+  pub group_by_type: bool,
+  /// List state for the grouped view, where rows include the group headers
+  pub grouped_list_state: ListState,
+  /// What each rendered row of the services list is: a unit index, or `None` for a group header
+  pub services_list_rows: Vec<Option<usize>>,
 }
 
+#[derive(Debug, Clone)]
 pub struct MenuItem {
   pub name: String,
   pub action: Action,
@@ -71,6 +465,13 @@ impl MenuItem {
   }
 }
 
+/// A line in the dependencies popup
+#[derive(Debug, Clone)]
+pub enum DependencyRow {
+  Header(String),
+  Unit(String),
+}
+
 pub struct StatefulList<T> {
   state: ListState,
   items: Vec<T>,
@@ -136,6 +537,27 @@ impl<T> StatefulList<T> {
     self.state.select(Some(i));
   }
 
+  // Move down by `n` items, stopping at the end of the list
+  fn next_n(&mut self, n: usize) {
+    if self.items.is_empty() {
+      return;
+    }
+    let i = match self.state.selected() {
+      Some(i) => i.saturating_add(n).min(self.items.len() - 1),
+      None => 0,
+    };
+    self.state.select(Some(i));
+  }
+
+  // Move up by `n` items, stopping at the start of the list
+  fn previous_n(&mut self, n: usize) {
+    let i = match self.state.selected() {
+      Some(i) => i.saturating_sub(n),
+      None => 0,
+    };
+    self.state.select(Some(i));
+  }
+
   fn select(&mut self, index: Option<usize>) {
     self.state.select(index);
   }
@@ -146,38 +568,132 @@ impl<T> StatefulList<T> {
 }
 
 impl Home {
-  pub fn new(scope: Scope, limit_units: &[String]) -> Self {
+  pub fn new(scope: Scope, limit_units: &[String], config: Config) -> Self {
     let limit_units = limit_units.to_vec();
-    Self { scope, limit_units, ..Default::default() }
+    let state_filter = config.state;
+    let show_logger = config.show_logger;
+    let log_lines = config.log_lines.clamp(MIN_LOG_LINES, MAX_LOG_LINES);
+    let colorize_logs = config.colorize_logs;
+    let structured_logs = config.structured_logs;
+    let relative_log_times = config.relative_log_times;
+    let hide_inactive = config.hide_inactive;
+    let hide_transient = config.hide_transient;
+    // main already complained if the regex is invalid
+    let log_grep = config.log_grep.as_deref().and_then(|p| LogGrep::new(p, config.log_grep_regex).ok());
+    let log_grep_regex = config.log_grep_regex;
+    let mut logger = Logger::default();
+    logger.colors = config.colors.clone();
+    Self {
+      scope,
+      limit_units,
+      logger,
+      config,
+      state_filter,
+      show_logger,
+      logger_percent: DEFAULT_LOGGER_PERCENT,
+      log_lines,
+      colorize_logs,
+      structured_logs,
+      relative_log_times,
+      hide_inactive,
+      hide_transient,
+      log_grep,
+      log_grep_regex,
+      is_root: nix::unistd::geteuid().is_root(),
+      ..Default::default()
+    }
   }
 
-  pub fn set_units(&mut self, units: Vec<UnitWithStatus>) {
-    self.all_units.clear();
-    for unit_status in units.into_iter() {
-      self.all_units.insert(unit_status.id(), unit_status);
+  fn try_restore_selection(&mut self) {
+    if let Some(unit) = &self.restore_selection {
+      let unit_index = self.all_units.get_index_of(unit);
+      if let Some(index) = self.filtered_units.items.iter().position(|&i| Some(i) == unit_index) {
+        self.restore_selection = None;
+        self.select(Some(index), true);
+      }
     }
-    self.refresh_filtered_units();
   }
 
   // Update units in-place, then filter the list
   // This is inefficient but it's fast enough
   // (on gen 13 i7: ~100 microseconds to update, ~100 microseconds to filter)
   // revisit if needed
-  pub fn update_units(&mut self, units: Vec<UnitWithStatus>) {
+  // `units` has every unit of `complete_scopes` (empty for a partial batch), so other units in them are gone
+  pub fn update_units(&mut self, units: Vec<UnitWithStatus>, complete_scopes: &[UnitScope]) {
     let now = std::time::Instant::now();
+    // sorting below shuffles the indices in the filtered list, so hang on to the selection by ID
+    let previously_selected = self.selected_service();
+    let previous_row = self.filtered_units.state.selected();
 
+    let mut changes = vec![];
+    if !complete_scopes.is_empty() {
+      let fetched: HashSet<UnitId> = units.iter().map(|u| u.id()).collect();
+      // retain keeps the order, so the list stays sorted
+      self.all_units.retain(|id, unit| {
+        let gone = complete_scopes.contains(&id.scope) && !fetched.contains(id);
+        // stay watched: systemd unloads inactive units, and they should still be watched when they're back
+        if gone && self.watched_units.contains(id) {
+          changes.push((unit.name.clone(), unit.activation_state.clone(), "gone".to_string()));
+        }
+        !gone
+      });
+      self.marked_units.retain(|id| self.all_units.contains_key(id));
+    }
+
+    let mut added_units = false;
     for unit in units {
       if let Some(existing) = self.all_units.get_mut(&unit.id()) {
+        if self.watched_units.contains(&unit.id()) && existing.activation_state != unit.activation_state {
+          changes.push((unit.name.clone(), existing.activation_state.clone(), unit.activation_state.clone()));
+        }
         existing.update(unit);
       } else {
+        if self.watched_units.contains(&unit.id()) {
+          changes.push((unit.name.clone(), "gone".to_string(), unit.activation_state.clone()));
+        }
         self.all_units.insert(unit.id(), unit);
+        added_units = true;
       }
     }
+    // new units get appended to the end, put them in their place (units can arrive in several batches)
+    if added_units {
+      self.all_units.sort_by_cached_key(|_, u| u.name.to_lowercase());
+    }
     info!("Updated units in {:?}", now.elapsed());
+    self.alert_watch_changes(changes);
 
     let now = std::time::Instant::now();
-    self.refresh_filtered_units();
+    self.refilter_units(previously_selected.clone());
     info!("Filtered units in {:?}", now.elapsed());
+    // the selected unit went away, select whatever took its place instead of jumping back to the top
+    if let (Some(unit), Some(row)) = (previously_selected, previous_row) {
+      if !self.all_units.contains_key(&unit) && !self.filtered_units.items.is_empty() {
+        self.select(Some(row.min(self.filtered_units.items.len() - 1)), true);
+      }
+    }
+    self.try_restore_selection();
+  }
+
+  // Announce state changes of watched units: (name, old state, new state)
+  fn alert_watch_changes(&mut self, changes: Vec<(String, String, String)>) {
+    if changes.is_empty() {
+      return;
+    }
+    let message = changes.iter().map(|(name, old, new)| format!("{}: {} → {}", name, old, new)).join(", ");
+    info!("Watched units changed state: {}", message);
+    // the worst news gets the color
+    let color = if changes.iter().any(|(_, _, new)| new == "failed") {
+      self.config.colors.error
+    } else if changes.iter().all(|(_, _, new)| new == "active") {
+      self.config.colors.active
+    } else {
+      self.config.colors.warning
+    };
+    self.watch_alert = Some(WatchAlert { time: chrono::Local::now(), message, color });
+    if self.config.watch_bell {
+      let mut output = crate::terminal::Output;
+      let _ = output.write_all(b"\x07").and_then(|_| output.flush());
+    }
   }
 
   pub fn next(&mut self) {
@@ -194,6 +710,82 @@ impl Home {
     self.logs_scroll_offset = 0;
   }
 
+  // e.g. "1243 units • 3 failed • 890 active • 350 inactive", for the status line
+  fn unit_counts_summary(&self) -> Vec<Span<'static>> {
+    let (mut failed, mut active, mut inactive) = (0, 0, 0);
+    for unit in self.all_units.values() {
+      if unit.is_failed() {
+        failed += 1;
+      } else if unit.is_active() {
+        active += 1;
+      } else if unit.activation_state == "inactive" {
+        inactive += 1;
+      }
+    }
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let failed_style = if failed > 0 { Style::default().fg(self.config.colors.failed) } else { dim };
+    let user = if self.is_root {
+      Span::styled("(root) ", Style::default().fg(self.config.colors.error))
+    } else {
+      Span::styled("(user) ", dim)
+    };
+    vec![
+      user,
+      Span::styled(format!("{} units", self.all_units.len()), dim),
+      Span::styled(" • ", dim),
+      Span::styled(format!("{} failed", failed), failed_style),
+      Span::styled(format!(" • {} active • {} inactive ", active, inactive), dim),
+    ]
+  }
+
+  // Unit file paths and enablement states are looked up once per unit and kept across refreshes. Drop them when they
+  // may have changed so the next selection looks them up again
+  fn invalidate_unit_cache(&mut self, unit: Option<&UnitId>) {
+    for (id, u) in self.all_units.iter_mut() {
+      if unit.is_none_or(|unit| unit == id) {
+        u.file_path = None;
+        u.enablement_state = None;
+        u.drop_in_paths = None;
+        u.environment = None;
+      }
+    }
+  }
+
+  // Open the unit file popup on some text. Without a title it's the selected unit's file
+  fn show_text(&mut self, title: Option<String>, contents: String) -> Action {
+    self.unit_file_contents = contents;
+    self.unit_file_title = title;
+    self.unit_file_scroll_offset = 0;
+    self.unit_file_filter.reset();
+    self.editing_unit_file_filter = false;
+    self.unit_file_return_mode = Mode::ServiceList;
+    Action::EnterMode(Mode::UnitFile)
+  }
+
+  pub fn set_logger_percent(&mut self, percent: u16) {
+    self.logger_percent = percent.clamp(MIN_LOGGER_PERCENT, MAX_LOGGER_PERCENT);
+  }
+
+  // Half the height of the services list, for D/U
+  fn half_page(&self) -> usize {
+    (self.services_list_area.height.saturating_sub(2) as usize / 2).max(1)
+  }
+
+  pub fn next_n(&mut self, n: usize) {
+    self.logs = vec![];
+    self.filtered_units.next_n(n);
+    self.get_logs();
+    self.logs_scroll_offset = 0;
+  }
+
+  pub fn previous_n(&mut self, n: usize) {
+    self.logs = vec![];
+    self.filtered_units.previous_n(n);
+    self.get_logs();
+    self.logs_scroll_offset = 0;
+  }
+
   pub fn select(&mut self, index: Option<usize>, refresh_logs: bool) {
     if refresh_logs {
       self.logs = vec![];
@@ -211,13 +803,45 @@ impl Home {
   }
 
   pub fn selected_service(&self) -> Option<UnitId> {
-    self.filtered_units.selected().map(|u| u.id())
+    self.selected_unit().map(|u| u.id())
+  }
+
+  fn selected_unit(&self) -> Option<&UnitWithStatus> {
+    self.filtered_units.selected().and_then(|&i| self.all_units.get_index(i)).map(|(_, u)| u)
+  }
+
+  // Ask for log previews of the units in `range` of the filtered list, skipping ones we've already asked for
+  fn request_log_previews(&mut self, range: std::ops::Range<usize>) {
+    let Some(log_preview_tx) = &self.log_preview_tx else { return };
+    for &i in self.filtered_units.items.iter().skip(range.start).take(range.len()) {
+      let Some((id, _)) = self.all_units.get_index(i) else { continue };
+      let id = id.clone();
+      if self.log_previews_requested.insert(id.clone()) {
+        let _ = log_preview_tx.send(id);
+      }
+    }
   }
 
   pub fn get_logs(&mut self) {
-    if let Some(selected) = self.filtered_units.selected() {
-      let unit_id = selected.id();
-      if let Err(e) = self.journalctl_tx.as_ref().unwrap().send(unit_id) {
+    // whatever was held back is for the logs being replaced
+    self.paused_log_lines.clear();
+    self.log_cursor = 0;
+    if let Some(selected) = self.selected_unit() {
+      let request = LogRequest {
+        unit: selected.id(),
+        lines: self.log_lines,
+        range: self.log_range,
+        priority: self.log_priority,
+        boot: self.log_boot,
+        since: self.log_since.clone(),
+        until: self.log_until.clone(),
+        structured: self.structured_logs,
+        active: selected.is_active(),
+        file_path_cached: matches!(selected.file_path, Some(Ok(_))),
+        enablement_state_cached: selected.enablement_state.is_some(),
+      };
+      self.logs_loading = true;
+      if let Err(e) = self.journalctl_tx.as_ref().unwrap().send(request) {
         warn!("Error sending unit name to journalctl thread: {}", e);
       }
     } else {
@@ -225,27 +849,185 @@ impl Home {
     }
   }
 
+  // Run the selected action menu item, asking for confirmation first if it's destructive
+  fn activate_menu_item(&mut self) -> Vec<Action> {
+    match self.menu_items.selected().cloned() {
+      Some(i) => self.confirm_or_dispatch(i, Mode::ActionMenu),
+      None => vec![Action::EnterMode(Mode::ServiceList)],
+    }
+  }
+
+  // Start/stop/restart the selected unit straight from the list or search, confirming like the menu would
+  fn quick_action(&mut self, operation: BatchOperation) -> Vec<Action> {
+    let Some(unit) = self.selected_service() else {
+      return vec![];
+    };
+    let item = MenuItem::new(operation.name(), operation.unit_action(unit));
+    self.confirm_or_dispatch(item, self.mode)
+  }
+
+  // Ask about `item` first if it's destructive, going back to `return_mode` if the answer is no
+  fn confirm_or_dispatch(&mut self, item: MenuItem, return_mode: Mode) -> Vec<Action> {
+    if self.needs_confirmation(&item.action) {
+      self.pending_action = Some(item);
+      self.confirm_return_mode = return_mode;
+      vec![Action::EnterMode(Mode::Confirm)]
+    } else {
+      vec![item.action]
+    }
+  }
+
+  // Move the dependencies popup's selection to the next/previous unit, skipping group headers
+  fn select_dependency(&mut self, forward: bool) {
+    for _ in 0..self.dependencies.items.len() {
+      if forward {
+        self.dependencies.next();
+      } else {
+        self.dependencies.previous();
+      }
+      if matches!(self.dependencies.selected(), Some(DependencyRow::Unit(_))) {
+        return;
+      }
+    }
+  }
+
+  // Indices of log lines (in display order, newest first) that contain the log search text
+  fn log_match_indices(&self) -> Vec<usize> {
+    let query = self.log_search_input.value();
+    if query.is_empty() {
+      return vec![];
+    }
+    visible_logs(&self.logs, self.log_grep.as_ref())
+      .enumerate()
+      .filter(|(_, l)| !find_matches(l, query).is_empty())
+      .map(|(i, _)| i)
+      .collect()
+  }
+
+  fn log_line_under_cursor(&self) -> Option<String> {
+    visible_logs(&self.logs, self.log_grep.as_ref()).nth(self.log_cursor).map(|l| l.text.clone())
+  }
+
+  /// Height of each visible log line once wrapped to the logs pane, in display order
+  fn log_line_heights(&self) -> impl Iterator<Item = usize> + '_ {
+    visible_logs(&self.logs, self.log_grep.as_ref()).map(|l| self.log_line_height(l))
+  }
+
+  /// How many rows `line` takes up in the logs pane once wrapped
+  fn log_line_height(&self, line: &LogLine) -> usize {
+    let width = self.logs_area.width.saturating_sub(2);
+    Paragraph::new(self.styled_log_line(line, self.log_search_input.value()))
+      .wrap(Wrap { trim: true })
+      .line_count(width)
+  }
+
+  fn styled_log_line<'a>(&self, l: &'a LogLine, log_query: &str) -> Line<'a> {
+    let highlight = self.config.colors.highlight;
+    // the journal knows the real priority of structured logs, otherwise it's a guess
+    let colors = &self.config.colors;
+    let style = |message: &str| match l
+      .priority
+      .map_or_else(|| log_level_color(message, colors), |p| priority_color(p, colors))
+    {
+      Some(color) if self.colorize_logs => Style::default().fg(color),
+      _ => Style::default(),
+    };
+    if let Some((date, rest)) = l.splitn(2, ' ').collect_tuple() {
+      if date.len() != 24 {
+        return Line::from(highlight_matches(l, log_query, style(l), highlight));
+      }
+      // worked out on every render so they stay current. Anything unparseable is shown as-is
+      let date_style = Style::default().fg(Color::DarkGray);
+      let mut spans = match self.relative_log_times.then(|| relative_log_time(date)).flatten() {
+        Some(relative) => highlight_matches(&relative, log_query, date_style, highlight)
+          .into_iter()
+          .map(|span| Span::styled(span.content.into_owned(), span.style))
+          .collect_vec(),
+        None => highlight_matches(date, log_query, date_style, highlight),
+      };
+      spans.push(Span::raw(" "));
+      spans.extend(highlight_matches(rest, log_query, style(rest), highlight));
+      Line::from(spans)
+    } else {
+      Line::from(highlight_matches(l, log_query, style(l), highlight))
+    }
+  }
+
+  // Scroll the logs to the next (or previous) line matching the log search, wrapping around at the ends
+  // The wrapped row each log search match starts on, which is what the scroll offset counts
+  fn log_match_rows(&self) -> Vec<usize> {
+    let line_tops = self
+      .log_line_heights()
+      .scan(0, |top, height| {
+        let line_top = *top;
+        *top += height;
+        Some(line_top)
+      })
+      .collect_vec();
+    self.log_match_indices().into_iter().filter_map(|i| line_tops.get(i).copied()).collect_vec()
+  }
+
+  fn jump_to_log_match(&mut self, forward: bool) {
+    let matches = self.log_match_rows();
+    let current = self.logs_scroll_offset as usize;
+    let target = if forward {
+      matches.iter().find(|&&row| row > current).or(matches.first())
+    } else {
+      matches.iter().rev().find(|&&row| row < current).or(matches.last())
+    };
+    if let Some(&target) = target {
+      self.logs_scroll_offset = target.min(u16::MAX as usize) as u16;
+    }
+  }
+
+  // Why a unit someone asked for isn't in `all_units`
+  fn unit_not_loaded_reason(&self) -> String {
+    if self.limit_units.is_empty() {
+      "It isn't loaded, maybe because it's inactive or doesn't exist".into()
+    } else {
+      format!("Only units matching {} are loaded", self.limit_units.join(", "))
+    }
+  }
+
   fn refresh_filtered_units(&mut self) {
-    let previously_selected = self.selected_service();
-    let search_value_lower = self.input.value().to_lowercase();
-    // TODO: use fuzzy find
-    let matching = self
+    self.refilter_units(self.selected_service());
+  }
+
+  // Only call this directly if `all_units` has been reordered since the selection was read,
+  // because the selection is an index into it
+  fn refilter_units(&mut self, previously_selected: Option<UnitId>) {
+    let search_value = self.input.value();
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut matching = self
       .all_units
       .values()
-      .filter(|u| u.short_name().to_lowercase().contains(&search_value_lower))
-      .cloned()
+      .enumerate()
+      .filter(|(_, u)| self.state_filter.is_none_or(|f| f.matches(u)))
+      .filter(|(_, u)| !(self.hide_inactive && u.is_dead()))
+      .filter(|(_, u)| !(self.hide_transient && u.is_transient()))
+      .filter_map(|(i, u)| {
+        if search_value.is_empty() {
+          return Some((0, vec![], i, u));
+        }
+        matcher.fuzzy_indices(u.short_name(), search_value).map(|(score, indices)| (score, indices, i, u))
+      })
       .collect_vec();
-    self.filtered_units.items = matching;
+    // sort_by_key is stable, so units that compare equal keep their alphabetical order
+    matching.sort_by_key(|(score, _, _, u)| (Reverse(*score), self.sort_mode.rank(u)));
+    if self.group_by_type {
+      // groups come first, then whatever order the search and sort mode gave us within each group
+      matching.sort_by_key(|(_, _, _, u)| unit_group(u).0);
+    }
+
+    let (items, match_indices): (Vec<_>, HashMap<_, _>) =
+      matching.into_iter().map(|(_, indices, i, _)| (i, (i, indices))).unzip();
+    self.filtered_units.items = items;
+    self.search_match_indices = match_indices;
 
     // try to select the same item we had selected before
-    // TODO: this is horrible, clean it up
     if let Some(previously_selected) = previously_selected {
-      if let Some(index) = self
-        .filtered_units
-        .items
-        .iter()
-        .position(|u| u.name == previously_selected.name && u.scope == previously_selected.scope)
-      {
+      let unit_index = self.all_units.get_index_of(&previously_selected);
+      if let Some(index) = self.filtered_units.items.iter().position(|&i| Some(i) == unit_index) {
         self.select(Some(index), false);
       } else {
         self.select(Some(0), true);
@@ -263,60 +1045,286 @@ impl Home {
   fn start_service(&mut self, service: UnitId) {
     let cancel_token = CancellationToken::new();
     let future = systemd::start_service(service.clone(), cancel_token.clone());
-    self.service_action(service, "Start".into(), cancel_token, future);
+    let systemctl_args = vec!["start".into(), service.name.clone()];
+    self.service_action(service, "Start".into(), systemctl_args, cancel_token, future);
   }
 
   fn stop_service(&mut self, service: UnitId) {
     let cancel_token = CancellationToken::new();
     let future = systemd::stop_service(service.clone(), cancel_token.clone());
-    self.service_action(service, "Stop".into(), cancel_token, future);
+    let systemctl_args = vec!["stop".into(), service.name.clone()];
+    self.service_action(service, "Stop".into(), systemctl_args, cancel_token, future);
   }
 
   fn reload_service(&mut self, service: UnitId) {
     let cancel_token = CancellationToken::new();
-    let future = systemd::reload(service.scope, cancel_token.clone());
-    self.service_action(service, "Reload".into(), cancel_token, future);
+    let future = systemd::reload_service(service.clone(), cancel_token.clone());
+    let systemctl_args = vec!["reload".into(), service.name.clone()];
+    self.service_action(service, "Reload".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn enable_service(&mut self, service: UnitId) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::enable_service(service.clone(), cancel_token.clone());
+    let systemctl_args = vec!["enable".into(), service.name.clone()];
+    self.service_action(service, "Enable".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn disable_service(&mut self, service: UnitId) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::disable_service(service.clone(), cancel_token.clone());
+    let systemctl_args = vec!["disable".into(), service.name.clone()];
+    self.service_action(service, "Disable".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn mask_service(&mut self, service: UnitId) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::mask_service(service.clone(), cancel_token.clone());
+    let systemctl_args = vec!["mask".into(), service.name.clone()];
+    self.service_action(service, "Mask".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn reset_failed(&mut self, service: UnitId) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::reset_failed(service.clone(), cancel_token.clone());
+    let systemctl_args = vec!["reset-failed".into(), service.name.clone()];
+    self.service_action(service, "Reset failed".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn freeze_service(&mut self, service: UnitId) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::freeze_service(service.clone(), cancel_token.clone());
+    let systemctl_args = vec!["freeze".into(), service.name.clone()];
+    self.service_action(service, "Freeze".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn thaw_service(&mut self, service: UnitId) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::thaw_service(service.clone(), cancel_token.clone());
+    let systemctl_args = vec!["thaw".into(), service.name.clone()];
+    self.service_action(service, "Thaw".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn unmask_service(&mut self, service: UnitId) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::unmask_service(service.clone(), cancel_token.clone());
+    let systemctl_args = vec!["unmask".into(), service.name.clone()];
+    self.service_action(service, "Unmask".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn force_stop_service(&mut self, service: UnitId) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::kill_service(service.clone(), "all", libc::SIGKILL, cancel_token.clone());
+    let systemctl_args = vec!["kill".into(), "--kill-whom=all".into(), "--signal=SIGKILL".into(), service.name.clone()];
+    self.service_action(service, "Force stop".into(), systemctl_args, cancel_token, future);
+  }
+
+  fn kill_service(&mut self, service: UnitId, signal: i32) {
+    let cancel_token = CancellationToken::new();
+    let future = systemd::kill_service(service.clone(), "main", signal, cancel_token.clone());
+    let systemctl_args =
+      vec!["kill".into(), "--kill-whom=main".into(), format!("--signal={}", signal), service.name.clone()];
+    self.service_action(service, format!("Signal {}", signal), systemctl_args, cancel_token, future);
+  }
+
+  // Destructive actions that should be confirmed before they're dispatched
+  fn needs_confirmation(&self, action: &Action) -> bool {
+    match action {
+      Action::ForceStopService(_) => true,
+      Action::StopService(_) | Action::RestartService(_) => self.config.confirm_stop_restart,
+      Action::RunBatch(BatchOperation::Stop | BatchOperation::Restart, _) => self.config.confirm_stop_restart,
+      // it restarts the unit
+      Action::ApplyUnitChanges(_) => self.config.confirm_stop_restart,
+      _ => false,
+    }
   }
 
   fn restart_service(&mut self, service: UnitId) {
     let cancel_token = CancellationToken::new();
     let future = systemd::restart_service(service.clone(), cancel_token.clone());
-    self.service_action(service, "Restart".into(), cancel_token, future);
+    let systemctl_args = vec!["restart".into(), service.name.clone()];
+    self.service_action(service, "Restart".into(), systemctl_args, cancel_token, future);
   }
 
-  fn service_action<Fut>(&mut self, service: UnitId, action_name: String, cancel_token: CancellationToken, action: Fut)
-  where
+  /// Run a service action in the background with a spinner. `systemctl_args` are the equivalent `systemctl` command,
+  /// used to offer a retry with pkexec if the action fails due to missing permissions
+  fn service_action<Fut>(
+    &mut self,
+    service: UnitId,
+    action_name: String,
+    systemctl_args: Vec<String>,
+    cancel_token: CancellationToken,
+    action: Fut,
+  ) where
     Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
   {
+    let description = format!("{} of {:?} service {}", action_name, service.scope, service.name);
+
+    // slow jobs (e.g. a database starting up) would otherwise just be a spinner, so keep an eye on the unit's state
     let tx = self.action_tx.clone().unwrap();
+    let scope = service.scope;
+    let action = async move {
+      let poller = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PROCESSING_STATUS_INTERVAL);
+        loop {
+          interval.tick().await;
+          if let Ok((active_state, sub_state)) = systemd::get_unit_state(&service).await {
+            let _ = tx.send(Action::SetProcessingStatus(format!("{} ({})", active_state, sub_state)));
+          }
+        }
+      });
+      let result = action.await;
+      poller.abort();
+      result
+    };
+    self.spawn_action(description, scope, systemctl_args, cancel_token, action);
+  }
 
-    self.cancel_token = Some(cancel_token.clone());
+  // Pick up an edited unit file (or drop-in) and restart the unit with it, under one spinner
+  fn apply_unit_changes(&mut self, service: UnitId) {
+    self.edited_units.remove(&service);
+    let cancel_token = CancellationToken::new();
+    let token = cancel_token.clone();
+    let unit = service.clone();
+    let future = async move {
+      systemd::reload(unit.scope, token.clone()).await?;
+      systemd::restart_service(unit, token).await
+    };
+    // pkexec can only retry one command. The daemon-reload is the part that needs privileges, restart after that
+    let systemctl_args = vec!["daemon-reload".into()];
+    self.service_action(service, "Apply changes".into(), systemctl_args, cancel_token, future);
+  }
 
-    let tx_clone = tx.clone();
-    let spinner_task = tokio::spawn(async move {
-      let mut interval = tokio::time::interval(Duration::from_millis(200));
-      loop {
-        interval.tick().await;
-        tx_clone.send(Action::SpinnerTick).unwrap();
+  fn daemon_reload(&mut self) {
+    let cancel_token = CancellationToken::new();
+    let scope = self.scope;
+    let token = cancel_token.clone();
+    let tx = self.action_tx.clone().unwrap();
+    let future = async move {
+      match scope {
+        Scope::Global => systemd::reload(UnitScope::Global, token).await?,
+        Scope::User => systemd::reload(UnitScope::User, token).await?,
+        Scope::All => {
+          systemd::reload(UnitScope::Global, token.clone()).await?;
+          systemd::reload(UnitScope::User, token).await?
+        },
       }
-    });
+      // unit files may have been added, removed, or changed
+      let _ = tx.send(Action::InvalidateUnitCache(None));
+      Ok(())
+    };
+    // a user scope reload doesn't need privileges, so only offer pkexec when the system manager is involved
+    let privileged_scope = match scope {
+      Scope::User => UnitScope::User,
+      Scope::Global | Scope::All => UnitScope::Global,
+    };
+    let description = format!("Daemon reload ({:?} scope)", scope);
+    self.spawn_action(description, privileged_scope, vec!["daemon-reload".into()], cancel_token, future);
+  }
 
-    tokio::spawn(async move {
-      tx.send(Action::EnterMode(Mode::Processing)).unwrap();
-      match action.await {
-        Ok(_) => {
-          info!("{} of {:?} service {} succeeded", action_name, service.scope, service.name);
+  // Run `operation` on each unit one at a time, carrying on past failures and reporting them all at the end
+  fn batch_action(&mut self, operation: BatchOperation, units: Vec<UnitId>) {
+    let cancel_token = CancellationToken::new();
+    let tx = self.action_tx.clone().unwrap();
+    let total = units.len();
+    let description = format!("{} of {} units", operation.name(), total);
+    // a pkexec retry only makes sense if they're all system units
+    let scope = if units.iter().all(|u| u.scope == UnitScope::Global) { UnitScope::Global } else { UnitScope::User };
+    let mut systemctl_args = vec![operation.systemctl_verb().to_string()];
+    systemctl_args.extend(units.iter().map(|u| u.name.clone()));
+
+    let token = cancel_token.clone();
+    let future = async move {
+      let mut failures = vec![];
+      for (done, unit) in units.into_iter().enumerate() {
+        let result = match operation {
+          BatchOperation::Start => systemd::start_service(unit.clone(), token.clone()).await,
+          BatchOperation::Stop => systemd::stop_service(unit.clone(), token.clone()).await,
+          BatchOperation::Restart => systemd::restart_service(unit.clone(), token.clone()).await,
+        };
+        if token.is_cancelled() {
+          anyhow::bail!("cancelled");
+        }
+        if let Err(e) = result {
+          failures.push(format!("{}: {}", unit.name, e));
+        }
+        let _ = tx.send(Action::SetBatchProgress(done + 1, total));
+      }
+      if !failures.is_empty() {
+        anyhow::bail!("{} of {} units failed:\n{}", failures.len(), total, failures.join("\n"));
+      }
+      Ok(())
+    };
+    self.spawn_action(description, scope, systemctl_args, cancel_token, future);
+    self.batch_progress = Some((0, total));
+  }
+
+  // Run a systemd operation in the background with a spinner, then show any error and refresh the list.
+  // `scope` and `systemctl_args` are used to offer a pkexec retry if it failed for lack of permissions
+  fn spawn_action<Fut>(
+    &mut self,
+    description: String,
+    scope: UnitScope,
+    systemctl_args: Vec<String>,
+    cancel_token: CancellationToken,
+    action: Fut,
+  ) where
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+  {
+    let tx = self.action_tx.clone().unwrap();
+
+    self.cancel_token = Some(cancel_token.clone());
+    self.batch_progress = None;
+    self.processing_status = None;
+
+    let tx_clone = tx.clone();
+    let spinner_task = tokio::spawn(async move {
+      let mut interval = tokio::time::interval(SPINNER_INTERVAL);
+      loop {
+        interval.tick().await;
+        tx_clone.send(Action::SpinnerTick).unwrap();
+      }
+    });
+
+    tokio::spawn(async move {
+      tx.send(Action::EnterMode(Mode::Processing)).unwrap();
+      match action.await {
+        Ok(_) => {
+          info!("{} succeeded", description);
+          // things like the enablement state may have changed
+          tx.send(Action::RefreshDetails).unwrap();
           tx.send(Action::EnterMode(Mode::ServiceList)).unwrap();
         },
         // would be nicer to check the error type here, but this is easier
         Err(_) if cancel_token.is_cancelled() => {
-          warn!("{} of {:?} service {} was cancelled", action_name, service.scope, service.name)
+          warn!("{} was cancelled", description)
         },
         Err(e) => {
-          error!("{} of {:?} service {} failed: {}", action_name, service.scope, service.name, e);
+          error!("{} failed: {}", description, e);
           let mut error_string = e.to_string();
+          tx.send(Action::OfferRetry).unwrap();
+
+          let error_name = systemd::dbus_error_name(&e);
+          let permission_denied = match &error_name {
+            Some(name) => systemd::is_permission_error(name),
+            None => error_string.contains("AccessDenied"),
+          };
+          // pkexec only helps for system units, user units already belong to us. Whether sudo is there with --host
+          // is only found out by trying
+          let can_elevate = systemd::remote_host().is_some() || crate::utils::find_in_path("pkexec").is_some();
+          if permission_denied && scope == UnitScope::Global && can_elevate {
+            tx.send(Action::OfferPrivilegedRetry(systemctl_args)).unwrap();
+          }
 
-          if error_string.contains("AccessDenied") {
+          if let Some(error_name) = error_name {
+            // the error message usually includes the name already, but not if it was wrapped with more context
+            if !error_string.contains(&error_name) {
+              error_string.push_str(&format!("\n\nD-Bus error: {}", error_name));
+            }
+            if let Some(hint) = systemd::dbus_error_hint(&error_name) {
+              error_string.push_str(&format!("\n\n{}", hint));
+            }
+          } else if error_string.contains("AccessDenied") {
             error_string.push('\n');
             error_string.push('\n');
             error_string.push_str("Try running this tool with sudo.");
@@ -340,25 +1348,31 @@ impl Home {
 impl Component for Home {
   fn init(&mut self, tx: UnboundedSender<Action>) -> anyhow::Result<()> {
     self.action_tx = Some(tx.clone());
+    let preview_tx = tx.clone();
     // TODO find a better name for these. They're used to run any async data loading that needs to happen after the selection is changed,
     // not just journalctl stuff
-    let (journalctl_tx, journalctl_rx) = std::sync::mpsc::channel::<UnitId>();
+    let (journalctl_tx, journalctl_rx) = std::sync::mpsc::channel::<LogRequest>();
     self.journalctl_tx = Some(journalctl_tx);
+    let journal_matchers = self.config.journal_matchers.clone();
+    let fixed_debounce = self.config.log_debounce.map(Duration::from_millis);
+    let follow_logs = self.config.follow_logs;
+    // a running average of how long journalctl takes to get the last few lines, in ms. 0 until we've measured it
+    let journalctl_latency = Arc::new(AtomicU64::new(0));
 
     // TODO: move into function
     tokio::task::spawn_blocking(move || {
       let mut last_follow_handle: Option<JoinHandle<()>> = None;
 
       loop {
-        let mut unit: UnitId = match journalctl_rx.recv() {
-          Ok(unit) => unit,
+        let mut request = match journalctl_rx.recv() {
+          Ok(request) => request,
           Err(_) => return,
         };
 
         // drain the channel, use the last value
-        while let Ok(service) = journalctl_rx.try_recv() {
-          info!("Skipping logs for {}...", unit.name);
-          unit = service;
+        while let Ok(next) = journalctl_rx.try_recv() {
+          info!("Skipping logs for {}...", request.unit.name);
+          request = next;
         }
 
         if let Some(handle) = last_follow_handle.take() {
@@ -366,76 +1380,319 @@ impl Component for Home {
           handle.abort();
         }
 
-        // lazy debounce to avoid spamming journalctl on slow connections/systems
-        std::thread::sleep(Duration::from_millis(100));
+        // debounce to avoid spamming journalctl when arrowing through units. The slower journalctl is, the more
+        // it's worth waiting to see if the selection moves on
+        let debounce = fixed_debounce.unwrap_or_else(|| match journalctl_latency.load(Ordering::Relaxed) {
+          0 => DEFAULT_LOG_DEBOUNCE,
+          latency => (Duration::from_millis(latency) / 2).clamp(MIN_LOG_DEBOUNCE, MAX_LOG_DEBOUNCE),
+        });
+        std::thread::sleep(debounce);
 
-        // get the unit file path
-        match systemd::get_unit_file_location(&unit) {
-          Ok(path) => {
-            let _ = tx.send(Action::SetUnitFilePath { unit: unit.clone(), path: Ok(path) });
-            let _ = tx.send(Action::Render);
-          },
-          Err(e) => {
-            // Fix this!!! Set the path to an error enum variant instead of a string
-            let _ =
-              tx.send(Action::SetUnitFilePath { unit: unit.clone(), path: Err("could not be determined".into()) });
-            let _ = tx.send(Action::Render);
-            error!("Error getting unit file path for {}: {}", unit.name, e);
-          },
+        // anything that came in while we waited replaces this request too
+        while let Ok(next) = journalctl_rx.try_recv() {
+          info!("Skipping logs for {}...", request.unit.name);
+          request = next;
         }
+        let unit = request.unit;
 
-        // First, get the N lines in a batch
-        info!("Getting logs for {}", unit.name);
-        let start = std::time::Instant::now();
+        // get the unit file path and its drop-ins, unless we already know them
+        if !request.file_path_cached {
+          let drop_ins_tx = tx.clone();
+          let drop_ins_unit = unit.clone();
+          let transient_tx = tx.clone();
+          let transient_unit = unit.clone();
+          tokio::spawn(async move {
+            match systemd::get_transient(&transient_unit).await {
+              Ok(transient) => {
+                let _ = transient_tx.send(Action::SetTransient { unit: transient_unit, transient });
+                let _ = transient_tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting whether {} is transient: {}", transient_unit.name, e),
+            }
+          });
 
-        let mut args = vec!["--quiet", "--output=short-iso", "--lines=500", "-u"];
+          tokio::spawn(async move {
+            match systemd::get_drop_in_paths(&drop_ins_unit).await {
+              Ok(paths) => {
+                let _ = drop_ins_tx.send(Action::SetDropInPaths { unit: drop_ins_unit, paths });
+                let _ = drop_ins_tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting drop-ins for {}: {}", drop_ins_unit.name, e),
+            }
+          });
 
-        args.push(&unit.name);
+          if unit.name.ends_with(".service") {
+            let env_tx = tx.clone();
+            let env_unit = unit.clone();
+            tokio::spawn(async move {
+              match systemd::get_environment(&env_unit).await {
+                Ok(environment) => {
+                  let _ = env_tx.send(Action::SetEnvironment { unit: env_unit, environment });
+                },
+                Err(e) => warn!("Error getting environment for {}: {}", env_unit.name, e),
+              }
+            });
+          }
 
-        if unit.scope == UnitScope::User {
-          args.push("--user");
+          match systemd::get_unit_file_location(&unit) {
+            Ok(path) => {
+              let _ = tx.send(Action::SetUnitFilePath { unit: unit.clone(), path: Ok(path) });
+              let _ = tx.send(Action::Render);
+            },
+            Err(e) => {
+              // Fix this!!! Set the path to an error enum variant instead of a string
+              let _ =
+                tx.send(Action::SetUnitFilePath { unit: unit.clone(), path: Err("could not be determined".into()) });
+              let _ = tx.send(Action::Render);
+              error!("Error getting unit file path for {}: {}", unit.name, e);
+            },
+          }
         }
 
-        match Command::new("journalctl").args(&args).output() {
-          Ok(output) => {
-            if output.status.success() {
-              info!("Got logs for {} in {:?}", unit.name, start.elapsed());
-              if let Ok(stdout) = std::str::from_utf8(&output.stdout) {
-                let mut logs = stdout.trim().split('\n').map(String::from).collect_vec();
+        if !request.enablement_state_cached {
+          let tx = tx.clone();
+          let unit = unit.clone();
+          tokio::spawn(async move {
+            match systemd::get_connection(unit.scope).await {
+              Ok(connection) => {
+                let state = systemd::get_unit_file_state(&connection, &unit.name).await;
+                let _ = tx.send(Action::SetEnablementState { unit, state });
+                let _ = tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting enablement state for {}: {}", unit.name, e),
+            }
+          });
+        }
 
-                if logs.is_empty() || logs[0].is_empty() {
-                  logs.push(String::from("No logs found/available. Maybe try relaunching with `sudo systemctl-tui`"));
-                }
-                let _ = tx.send(Action::SetLogs { unit: unit.clone(), logs });
+        {
+          let tx = tx.clone();
+          let unit = unit.clone();
+          tokio::spawn(async move {
+            match systemd::get_state_change_times(&unit).await {
+              Ok((active_enter, inactive_enter)) => {
+                let _ = tx.send(Action::SetStateChangeTimes { unit, active_enter, inactive_enter });
                 let _ = tx.send(Action::Render);
-              } else {
-                warn!("Error parsing stdout for {}", unit.name);
-              }
-            } else {
-              warn!("Error getting logs for {}: {}", unit.name, String::from_utf8_lossy(&output.stderr));
+              },
+              Err(e) => warn!("Error getting state change times for {}: {}", unit.name, e),
             }
-          },
-          Err(e) => warn!("Error getting logs for {}: {}", unit.name, e),
+          });
+        }
+
+        if request.active {
+          let tx = tx.clone();
+          let unit = unit.clone();
+          tokio::spawn(async move {
+            match systemd::get_freezer_state(&unit).await {
+              Ok(state) => {
+                let _ = tx.send(Action::SetFreezerState { unit, state });
+                let _ = tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting freezer state for {}: {}", unit.name, e),
+            }
+          });
+        }
+
+        if unit.name.ends_with(".socket") {
+          let tx = tx.clone();
+          let unit = unit.clone();
+          tokio::spawn(async move {
+            match systemd::get_socket_info(&unit).await {
+              Ok(info) => {
+                let _ = tx.send(Action::SetSocketInfo { unit, info });
+                let _ = tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting socket info for {}: {}", unit.name, e),
+            }
+          });
+        }
+
+        if unit.name.ends_with(".timer") {
+          let tx = tx.clone();
+          let unit = unit.clone();
+          tokio::spawn(async move {
+            match systemd::get_timer_times(&unit).await {
+              Ok((next_elapse, last_trigger)) => {
+                let _ = tx.send(Action::SetTimerTimes { unit, next_elapse, last_trigger });
+                let _ = tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting timer info for {}: {}", unit.name, e),
+            }
+          });
+        }
+
+        if unit.name.ends_with(".service") {
+          let tx = tx.clone();
+          let unit = unit.clone();
+          tokio::spawn(async move {
+            match systemd::get_restart_count(&unit).await {
+              Ok(n_restarts) => {
+                let _ = tx.send(Action::SetRestartCount { unit, n_restarts });
+                let _ = tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting restart count for {}: {}", unit.name, e),
+            }
+          });
+        }
+
+        if request.active && unit.name.ends_with(".service") {
+          let tx = tx.clone();
+          let unit = unit.clone();
+          tokio::spawn(async move {
+            let pid = match systemd::get_connection(unit.scope).await {
+              Ok(connection) => systemd::get_main_pid(&connection, &unit.name).await.map_err(anyhow::Error::from),
+              Err(e) => Err(e),
+            };
+            match pid {
+              Ok(pid) => {
+                let _ = tx.send(Action::SetMainPid { unit: unit.clone(), pid });
+                let _ = tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting main PID for {}: {}", unit.name, e),
+            }
+
+            match systemd::get_resource_usage(&unit).await {
+              Ok((memory_current, cpu_usage)) => {
+                let _ = tx.send(Action::SetResourceUsage { unit, memory_current, cpu_usage });
+                let _ = tx.send(Action::Render);
+              },
+              Err(e) => warn!("Error getting resource usage for {}: {}", unit.name, e),
+            }
+          });
         }
 
-        // Then follow the logs
-        // Splitting this into two commands is a bit of a hack that makes it easier to get the initial batch of logs
-        // This does mean that we'll miss any logs that are written between the two commands, low enough risk for now
+        // Fetch a batch of logs, then follow them. Both happen in a task that gets aborted when the selection changes,
+        // because fetching everything (--lines=all) can take a long time for chatty units
+        let match_args = journal_match_args(&unit, journal_matchers.get(&unit.name));
+        let lines_arg = match request.range {
+          LogRange::Tail => format!("--lines={}", request.lines),
+          LogRange::Boot | LogRange::All => "--lines=all".to_string(),
+        };
+        let priority_arg = request.priority.map(|p| format!("--priority={}", p));
+        let output_arg = if request.structured { "--output=json" } else { "--output=short-iso" };
+        let parse_line =
+          move |line: &str| if request.structured { LogLine::from_json(line) } else { LogLine::plain(line) };
+        let boot_arg = match (request.boot, request.range) {
+          (Some(b), _) => Some(format!("--boot={}", b)),
+          (None, LogRange::Boot) => Some("--boot".to_string()),
+          (None, _) => None,
+        };
+
+        let since_arg = request.since.as_ref().map(|s| format!("--since={}", s));
+        let until_arg = request.until.as_ref().map(|u| format!("--until={}", u));
+        let time_bounded = since_arg.is_some() || until_arg.is_some();
+
+        // fetching a whole boot or a time range takes longer, it's not a fair measure of journalctl's speed
+        let journalctl_latency = (request.range == LogRange::Tail && !time_bounded).then(|| journalctl_latency.clone());
         let tx = tx.clone();
         last_follow_handle = Some(tokio::spawn(async move {
-          let mut command = tokio::process::Command::new("journalctl");
-          command.arg("-u");
-          command.arg(unit.name.clone());
-          command.arg("--output=short-iso");
-          command.arg("--follow");
-          command.arg("--lines=0");
-          command.arg("--quiet");
-          command.stdout(Stdio::piped());
-          command.stderr(Stdio::piped());
+          info!("Getting logs for {}", unit.name);
+          let start = std::time::Instant::now();
+
+          // the cursor of the last entry lets the follow command below pick up exactly where this one left off
+          let mut args = vec!["--quiet", output_arg, "--show-cursor", lines_arg.as_str()];
+          args.extend(match_args.iter().map(String::as_str));
+          args.extend(priority_arg.as_deref());
+          args.extend(boot_arg.as_deref());
+          args.extend(since_arg.as_deref());
+          args.extend(until_arg.as_deref());
+          if unit.scope == UnitScope::User {
+            args.push("--user");
+          }
+          let mut command = tokio::process::Command::from(systemd::host_command("journalctl", args));
+          // don't leave a big fetch running in the background if we get aborted
+          command.kill_on_drop(true);
+
+          // keep the spinner in the logs title going until the batch shows up
+          let output = {
+            let fetch = command.output();
+            tokio::pin!(fetch);
+            let mut spinner_interval = tokio::time::interval(SPINNER_INTERVAL);
+            loop {
+              tokio::select! {
+                output = &mut fetch => break output,
+                _ = spinner_interval.tick() => {
+                  let _ = tx.send(Action::SpinnerTick);
+                },
+              }
+            }
+          };
+
+          let mut cursor = None;
+          match output {
+            Ok(output) => {
+              if output.status.success() {
+                let elapsed = start.elapsed();
+                info!("Got logs for {} in {:?}", unit.name, elapsed);
+                if let Some(latency) = &journalctl_latency {
+                  let elapsed = elapsed.as_millis().max(1) as u64;
+                  let average = match latency.load(Ordering::Relaxed) {
+                    0 => elapsed,
+                    previous => (previous * 3 + elapsed) / 4,
+                  };
+                  latency.store(average, Ordering::Relaxed);
+                }
+                if let Ok(stdout) = std::str::from_utf8(&output.stdout) {
+                  let mut lines = stdout.trim().split('\n').collect_vec();
+                  if let Some(last) = lines.pop_if(|l| l.starts_with("-- cursor: ")) {
+                    cursor = Some(last.trim_start_matches("-- cursor: ").to_string());
+                  }
+
+                  // blank lines can be part of a multi-line message, only a completely empty result means no logs
+                  let logs = if lines.iter().all(|l| l.is_empty()) {
+                    vec![LogLine::plain("No logs found/available. Maybe try relaunching with `sudo systemctl-tui`")]
+                  } else {
+                    lines.into_iter().map(parse_line).collect_vec()
+                  };
+                  let _ = tx.send(Action::SetLogs { unit: unit.clone(), logs });
+                } else {
+                  warn!("Error parsing stdout for {}", unit.name);
+                  let logs = vec![LogLine::plain("Logs were not valid UTF-8")];
+                  let _ = tx.send(Action::SetLogs { unit: unit.clone(), logs });
+                }
+              } else {
+                // e.g. asking for a boot that isn't in the journal. Say so instead of spinning forever
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                warn!("Error getting logs for {}: {}", unit.name, stderr);
+                let _ = tx.send(Action::SetLogs { unit: unit.clone(), logs: vec![LogLine::plain(stderr.trim())] });
+                // most likely a time journalctl couldn't parse, that's worth a popup
+                if time_bounded {
+                  let _ = tx.send(Action::EnterError(format!("Invalid log time range: {}", stderr.trim())));
+                }
+              }
+            },
+            Err(e) => {
+              warn!("Error getting logs for {}: {}", unit.name, e);
+              let logs = vec![LogLine::plain(format!("Failed to run journalctl: {}", e))];
+              let _ = tx.send(Action::SetLogs { unit: unit.clone(), logs });
+            },
+          }
+          let _ = tx.send(Action::Render);
+
+          // a previous boot is over, nothing new will show up. Same for a bounded time range
+          if request.boot.is_some_and(|b| b < 0) || time_bounded || !follow_logs {
+            return;
+          }
 
+          // Then follow the logs
+          // Splitting this into two commands makes it easy to tell when the initial batch is done. Starting right after
+          // the batch's last entry means nothing written in between gets missed
+          let start_arg = match &cursor {
+            Some(cursor) => format!("--after-cursor={}", cursor),
+            // no entries yet, so there's nothing to pick up after
+            None => "--lines=0".to_string(),
+          };
+          let mut args: Vec<&str> = match_args.iter().map(String::as_str).collect();
+          args.extend([output_arg, "--follow", start_arg.as_str(), "--quiet"]);
+          args.extend(priority_arg.as_deref());
+          args.extend(boot_arg.as_deref());
           if unit.scope == UnitScope::User {
-            command.arg("--user");
+            args.push("--user");
           }
+          let mut command = tokio::process::Command::from(systemd::host_command("journalctl", args));
+          command.stdout(Stdio::piped());
+          command.stderr(Stdio::piped());
+          // this task gets aborted when the selection changes, don't leave journalctl following in the background
+          command.kill_on_drop(true);
 
           let mut child = command.spawn().expect("failed to execute process");
 
@@ -444,16 +1701,98 @@ impl Component for Home {
           let reader = tokio::io::BufReader::new(stdout);
           let mut lines = reader.lines();
           while let Some(line) = lines.next_line().await.unwrap() {
-            let _ = tx.send(Action::AppendLogLine { unit: unit.clone(), line });
+            let _ = tx.send(Action::AppendLogLine { unit: unit.clone(), line: parse_line(&line) });
             let _ = tx.send(Action::Render);
           }
         }));
       }
     });
+
+    // Fetch the last log line of units shown in the list, a few at a time
+    let (log_preview_tx, log_preview_rx) = mpsc::unbounded_channel::<UnitId>();
+    self.log_preview_tx = Some(log_preview_tx);
+    let journal_matchers = self.config.journal_matchers.clone();
+    tokio::spawn(async move {
+      UnboundedReceiverStream::new(log_preview_rx)
+        .for_each_concurrent(LOG_PREVIEW_CONCURRENCY, |unit| {
+          let preview_tx = preview_tx.clone();
+          let match_args = journal_match_args(&unit, journal_matchers.get(&unit.name));
+          async move {
+            let mut args: Vec<&str> = match_args.iter().map(String::as_str).collect();
+            args.extend(["--quiet", "--output=cat", "--lines=1"]);
+            if unit.scope == UnitScope::User {
+              args.push("--user");
+            }
+            let mut command = tokio::process::Command::from(systemd::host_command("journalctl", args));
+
+            let line = match command.output().await {
+              Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+              Ok(output) => {
+                warn!("Error getting log preview for {}: {}", unit.name, String::from_utf8_lossy(&output.stderr));
+                return;
+              },
+              Err(e) => {
+                warn!("Error getting log preview for {}: {}", unit.name, e);
+                return;
+              },
+            };
+            let _ = preview_tx.send(Action::SetLogPreview { unit, line });
+            let _ = preview_tx.send(Action::DebouncedRender);
+          }
+        })
+        .await;
+    });
     Ok(())
   }
 
+  fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Vec<Action> {
+    let position = Position::new(mouse.column, mouse.row);
+    // index of the list item under the cursor, given the list's area (with borders) and scroll offset
+    let item_at = |area: Rect, offset: usize| -> Option<usize> {
+      let inner = area.inner(Margin::new(1, 1));
+      inner.contains(position).then(|| offset + (position.y - inner.y) as usize)
+    };
+
+    match (self.mode, mouse.kind) {
+      (Mode::ActionMenu, MouseEventKind::Down(MouseButton::Left)) => {
+        match item_at(self.action_menu_area, self.menu_items.state.offset()) {
+          Some(index) if index < self.menu_items.items.len() => {
+            self.menu_items.state.select(Some(index));
+            self.activate_menu_item()
+          },
+          _ => vec![],
+        }
+      },
+      (Mode::ServiceList | Mode::Search, MouseEventKind::Down(MouseButton::Left)) => {
+        let index = if self.group_by_type {
+          // rows include group headers, which can't be selected
+          item_at(self.services_list_area, self.grouped_list_state.offset())
+            .and_then(|row| self.services_list_rows.get(row).copied().flatten())
+        } else {
+          item_at(self.services_list_area, self.filtered_units.state.offset())
+        };
+        match index {
+          Some(index) if index < self.filtered_units.items.len() => {
+            self.select(Some(index), true);
+            vec![Action::EnterMode(Mode::ServiceList)]
+          },
+          _ => vec![],
+        }
+      },
+      (_, MouseEventKind::ScrollDown) if self.logs_area.contains(position) => {
+        vec![Action::ScrollDown(1), Action::Render]
+      },
+      (_, MouseEventKind::ScrollUp) if self.logs_area.contains(position) => vec![Action::ScrollUp(1), Action::Render],
+      _ => vec![],
+    }
+  }
+
   fn handle_key_events(&mut self, key: KeyEvent) -> Vec<Action> {
+    // the user has taken over, don't yank the selection around if the last selected unit shows up later
+    self.restore_selection = None;
+    // they've seen it
+    self.watch_alert = None;
+
     if key.modifiers.contains(KeyModifiers::CONTROL) {
       match key.code {
         KeyCode::Char('c') => return vec![Action::Quit],
@@ -461,9 +1800,38 @@ impl Component for Home {
         KeyCode::Char('z') => return vec![Action::Suspend],
         KeyCode::Char('f') => return vec![Action::EnterMode(Mode::Search)],
         KeyCode::Char('l') => return vec![Action::ToggleShowLogger],
+        // the logger pane is at the bottom, so up makes it taller
+        KeyCode::Up if self.show_logger => {
+          self.set_logger_percent(self.logger_percent.saturating_add(LOGGER_PERCENT_STEP));
+          return vec![Action::Render];
+        },
+        KeyCode::Down if self.show_logger => {
+          self.set_logger_percent(self.logger_percent.saturating_sub(LOGGER_PERCENT_STEP));
+          return vec![Action::Render];
+        },
+        KeyCode::Char('r') => return vec![Action::DaemonReload],
+        // quick actions on the selected unit, so "find it and restart it" doesn't need the menu
+        KeyCode::Char('s') if matches!(self.mode, Mode::ServiceList | Mode::Search) => {
+          return self.quick_action(BatchOperation::Start);
+        },
+        KeyCode::Char('x') if matches!(self.mode, Mode::ServiceList | Mode::Search) => {
+          return self.quick_action(BatchOperation::Stop);
+        },
+        KeyCode::Char('t') if matches!(self.mode, Mode::ServiceList | Mode::Search) => {
+          return self.quick_action(BatchOperation::Restart);
+        },
+        KeyCode::Char('g') => return vec![Action::EnterMode(Mode::LogSearch)],
         // vim keybindings, apparently
         KeyCode::Char('d') => return vec![Action::ScrollDown(1), Action::Render],
         KeyCode::Char('u') => return vec![Action::ScrollUp(1), Action::Render],
+        KeyCode::Char('j') if matches!(self.mode, Mode::ServiceList | Mode::Search) => {
+          self.next_n(LIST_JUMP_SIZE);
+          return vec![Action::EnterMode(Mode::ServiceList)];
+        },
+        KeyCode::Char('k') if matches!(self.mode, Mode::ServiceList | Mode::Search) => {
+          self.previous_n(LIST_JUMP_SIZE);
+          return vec![Action::EnterMode(Mode::ServiceList)];
+        },
         _ => (),
       }
     }
@@ -484,8 +1852,31 @@ impl Component for Home {
 
     match self.mode {
       Mode::ServiceList => {
+        // any key other than a second `g` cancels a pending `gg`
+        let pending_g = std::mem::take(&mut self.pending_g);
         match key.code {
           KeyCode::Char('q') => vec![Action::Quit],
+          KeyCode::Char('g') if pending_g => {
+            self.select(Some(0), true);
+            vec![Action::Render]
+          },
+          KeyCode::Char('g') => {
+            self.pending_g = true;
+            vec![]
+          },
+          // ctrl+D/ctrl+U scroll the logs, so half a page of the list is D/U
+          KeyCode::Char('D') => {
+            self.next_n(self.half_page());
+            vec![Action::Render]
+          },
+          KeyCode::Char('U') => {
+            self.previous_n(self.half_page());
+            vec![Action::Render]
+          },
+          KeyCode::Char('G') => {
+            self.select(Some(self.filtered_units.items.len().saturating_sub(1)), true);
+            vec![Action::Render]
+          },
           KeyCode::Up | KeyCode::Char('k') => {
             // if we're filtering the list, and we're at the top, and there's text in the search box, go to search mode
             if self.filtered_units.state.selected() == Some(0) {
@@ -499,19 +1890,246 @@ impl Component for Home {
             self.next();
             vec![Action::Render]
           },
+          KeyCode::Char('}') => {
+            self.next_n(LIST_JUMP_SIZE);
+            vec![Action::Render]
+          },
+          KeyCode::Char('{') => {
+            self.previous_n(LIST_JUMP_SIZE);
+            vec![Action::Render]
+          },
           KeyCode::Char('/') => vec![Action::EnterMode(Mode::Search)],
+          KeyCode::Char('c') => vec![Action::CopyDetails],
+          KeyCode::Char('o') => vec![Action::OpenUnitFileDirectory],
+          KeyCode::Char('a') => match self.selected_service() {
+            Some(unit) => {
+              let item = MenuItem::new("Apply changes (daemon-reload + restart)", Action::ApplyUnitChanges(unit));
+              self.confirm_or_dispatch(item, Mode::ServiceList)
+            },
+            None => vec![],
+          },
+          KeyCode::Char('L') => vec![Action::ToggleLogPreviews],
+          KeyCode::Char('t') => vec![Action::ToggleGroupByType],
+          KeyCode::Char('T') => vec![Action::ShowTimers],
+          KeyCode::Char('C') => vec![Action::ToggleLogColors],
+          KeyCode::Char('O') => vec![Action::ToggleStructuredLogs],
+          KeyCode::Char('R') => vec![Action::ToggleRelativeLogTimes],
+          KeyCode::Char('|') => {
+            // start from the current grep so it can be tweaked
+            if let Some(grep) = &self.log_grep {
+              self.log_grep_input = Input::new(grep.pattern().to_string());
+              self.log_grep_regex = matches!(grep, LogGrep::Regex(_));
+            }
+            vec![Action::EnterMode(Mode::LogGrep)]
+          },
+          KeyCode::Char('J') => match self.selected_service() {
+            Some(unit) => {
+              let mut args = journal_match_args(&unit, self.config.journal_matchers.get(&unit.name));
+              if unit.scope == UnitScope::User {
+                args.push("--user".into());
+              }
+              vec![Action::OpenJournalInPager(args)]
+            },
+            None => vec![],
+          },
+          KeyCode::Char('s') => vec![Action::CycleSortMode],
+          KeyCode::Char('h') => vec![Action::ToggleHideInactive],
+          KeyCode::Char('H') => vec![Action::ToggleHideTransient],
+          KeyCode::Char('z') => vec![Action::ToggleLogsPaused],
+          KeyCode::Char('l') | KeyCode::Right if !self.logs.is_empty() => {
+            // start on the first line in view, so nothing jumps around
+            let offset = self.logs_scroll_offset as usize;
+            let mut top = 0;
+            let first_in_view = self
+              .log_line_heights()
+              .position(|height| {
+                top += height;
+                top > offset
+              })
+              .unwrap_or(0);
+            self.log_cursor = first_in_view;
+            vec![Action::EnterMode(Mode::LogLines), Action::Render]
+          },
+          KeyCode::Char('W') => match self.selected_service() {
+            Some(unit) => vec![Action::ToggleWatch(unit)],
+            None => vec![],
+          },
+          KeyCode::Char('S') => vec![Action::SetScope(self.scope.next())],
+          KeyCode::Char('+') => vec![Action::SetLogLines(self.log_lines.saturating_mul(2))],
+          KeyCode::Char('-') => vec![Action::SetLogLines(self.log_lines / 2)],
+          // p shows fewer, more severe logs. P goes the other way
+          KeyCode::Char('p') => vec![Action::SetLogPriority(match self.log_priority {
+            None => Some(LOG_PRIORITIES.len() as u8 - 1),
+            Some(0) => None,
+            Some(p) => Some(p - 1),
+          })],
+          KeyCode::Char('P') => vec![Action::SetLogPriority(match self.log_priority {
+            None => Some(0),
+            Some(p) if p as usize >= LOG_PRIORITIES.len() - 1 => None,
+            Some(p) => Some(p + 1),
+          })],
+          // b goes back a boot, B goes forward (and past the current boot to all boots)
+          KeyCode::Char('b') => vec![Action::SetLogBoot(Some(self.log_boot.map_or(0, |b| b - 1)))],
+          KeyCode::Char('B') => vec![Action::SetLogBoot(match self.log_boot {
+            None | Some(0) => None,
+            Some(b) => Some(b + 1),
+          })],
+          KeyCode::Char('f') => vec![Action::SetLogRange(self.log_range.next())],
+          KeyCode::Char('w') => {
+            self.log_since_input = Input::new(self.log_since.clone().unwrap_or_default());
+            self.log_until_input = Input::new(self.log_until.clone().unwrap_or_default());
+            self.editing_log_until = false;
+            vec![Action::EnterMode(Mode::LogTimeRange)]
+          },
+          KeyCode::Char('F') => match self.state_filter {
+            Some(StateFilter::Failed) => vec![Action::SetStateFilter(None)],
+            _ => vec![Action::SetStateFilter(Some(StateFilter::Failed))],
+          },
+          KeyCode::Char('v') => {
+            if let Some(unit) = self.selected_service() {
+              if !self.marked_units.remove(&unit) {
+                self.marked_units.insert(unit);
+              }
+              self.next();
+            }
+            vec![Action::Render]
+          },
+          KeyCode::Esc if !self.marked_units.is_empty() => {
+            self.marked_units.clear();
+            vec![Action::Render]
+          },
+          KeyCode::Esc if self.log_grep.is_some() => vec![Action::SetLogGrep(None)],
+          KeyCode::Esc if self.state_filter.is_some() => vec![Action::SetStateFilter(None)],
+          KeyCode::Char('n') if !self.log_search_input.value().is_empty() => {
+            self.jump_to_log_match(true);
+            vec![Action::Render]
+          },
+          KeyCode::Char('N') if !self.log_search_input.value().is_empty() => {
+            self.jump_to_log_match(false);
+            vec![Action::Render]
+          },
           KeyCode::Enter | KeyCode::Char(' ') => vec![Action::EnterMode(Mode::ActionMenu)],
           _ => vec![],
         }
       },
       Mode::Help => match key.code {
         KeyCode::Esc | KeyCode::Enter => vec![Action::ToggleHelp],
+        // for terminals too short to fit it all
+        KeyCode::Down | KeyCode::Char('j') => {
+          self.help_scroll_offset = self.help_scroll_offset.saturating_add(1);
+          vec![Action::Render]
+        },
+        KeyCode::Up | KeyCode::Char('k') => {
+          self.help_scroll_offset = self.help_scroll_offset.saturating_sub(1);
+          vec![Action::Render]
+        },
         _ => vec![],
       },
-      Mode::Error => match key.code {
+      Mode::Dependencies => match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => vec![Action::EnterMode(Mode::ServiceList)],
+        KeyCode::Down | KeyCode::Char('j') => {
+          self.select_dependency(true);
+          vec![Action::Render]
+        },
+        KeyCode::Up | KeyCode::Char('k') => {
+          self.select_dependency(false);
+          vec![Action::Render]
+        },
+        KeyCode::Enter => match (self.dependencies.selected(), &self.dependencies_of) {
+          (Some(DependencyRow::Unit(name)), Some(of)) => {
+            vec![Action::JumpToUnit(UnitId { name: name.clone(), scope: of.scope })]
+          },
+          _ => vec![],
+        },
+        _ => vec![],
+      },
+      Mode::Timers => match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => vec![Action::EnterMode(Mode::ServiceList)],
+        KeyCode::Down | KeyCode::Char('j') => {
+          self.timers.next();
+          vec![Action::Render]
+        },
+        KeyCode::Up | KeyCode::Char('k') => {
+          self.timers.previous();
+          vec![Action::Render]
+        },
+        KeyCode::Enter => match self.timers.selected() {
+          Some(timer) => {
+            vec![Action::JumpToUnit(UnitId { name: timer.activates.clone(), scope: timer.timer.scope })]
+          },
+          None => vec![],
+        },
+        _ => vec![],
+      },
+      Mode::UnitFile if self.editing_unit_file_filter => match key.code {
+        KeyCode::Esc => {
+          self.unit_file_filter.reset();
+          self.editing_unit_file_filter = false;
+          vec![Action::Render]
+        },
+        KeyCode::Enter => {
+          self.editing_unit_file_filter = false;
+          vec![Action::Render]
+        },
+        _ => {
+          self.unit_file_filter.handle_event(&crossterm::event::Event::Key(key));
+          self.unit_file_scroll_offset = 0;
+          vec![Action::Render]
+        },
+      },
+      Mode::UnitFile => match key.code {
+        KeyCode::Char('/') => {
+          self.editing_unit_file_filter = true;
+          vec![Action::Render]
+        },
+        KeyCode::Esc | KeyCode::Char('q') => vec![Action::EnterMode(self.unit_file_return_mode)],
+        KeyCode::Down | KeyCode::Char('j') => vec![Action::ScrollDown(1), Action::Render],
+        KeyCode::Up | KeyCode::Char('k') => vec![Action::ScrollUp(1), Action::Render],
+        _ => vec![],
+      },
+      Mode::LogLines => match key.code {
+        KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('q') => {
+          vec![Action::EnterMode(Mode::ServiceList)]
+        },
+        KeyCode::Down | KeyCode::Char('j') => vec![Action::ScrollDown(1), Action::Render],
+        KeyCode::Up | KeyCode::Char('k') => vec![Action::ScrollUp(1), Action::Render],
+        KeyCode::Char('y') => match self.log_line_under_cursor() {
+          Some(line) => match clipboard_anywhere::set_clipboard(&line) {
+            Ok(_) => vec![Action::Render],
+            Err(e) => vec![Action::EnterError(format!("Error copying to clipboard: {}", e))],
+          },
+          None => vec![],
+        },
+        KeyCode::Enter => match self.log_line_under_cursor() {
+          Some(line) => {
+            let action = self.show_text(Some("─Log line".into()), line);
+            self.unit_file_return_mode = Mode::LogLines;
+            vec![action, Action::Render]
+          },
+          None => vec![],
+        },
+        _ => vec![],
+      },
+      Mode::Success => match key.code {
         KeyCode::Esc | KeyCode::Enter => vec![Action::EnterMode(Mode::ServiceList)],
         _ => vec![],
       },
+      Mode::Error => match key.code {
+        KeyCode::Esc | KeyCode::Enter => {
+          self.privileged_retry = None;
+          self.retry_action = None;
+          vec![Action::EnterMode(Mode::ServiceList)]
+        },
+        KeyCode::Char('r') if self.retry_action.is_some() => {
+          self.privileged_retry = None;
+          let item = MenuItem::new("Retry", self.retry_action.take().unwrap());
+          self.confirm_or_dispatch(item, Mode::ServiceList)
+        },
+        KeyCode::Char('p') if self.privileged_retry.is_some() => {
+          vec![Action::RunPrivileged(self.privileged_retry.take().unwrap())]
+        },
+        _ => vec![],
+      },
       Mode::Search => match key.code {
         KeyCode::Esc => vec![Action::EnterMode(Mode::ServiceList)],
         KeyCode::Enter => vec![Action::EnterMode(Mode::ActionMenu)],
@@ -534,6 +2152,62 @@ impl Component for Home {
           vec![Action::Render]
         },
       },
+      Mode::LogGrep => match key.code {
+        KeyCode::Esc => vec![Action::EnterMode(Mode::ServiceList)],
+        KeyCode::Tab => {
+          self.log_grep_regex = !self.log_grep_regex;
+          vec![Action::Render]
+        },
+        KeyCode::Enter => {
+          let pattern = self.log_grep_input.value();
+          if pattern.is_empty() {
+            return vec![Action::EnterMode(Mode::ServiceList), Action::SetLogGrep(None)];
+          }
+          match LogGrep::new(pattern, self.log_grep_regex) {
+            Ok(grep) => vec![Action::EnterMode(Mode::ServiceList), Action::SetLogGrep(Some(grep))],
+            Err(e) => vec![Action::EnterError(format!("Invalid regex: {}", e))],
+          }
+        },
+        _ => {
+          self.log_grep_input.handle_event(&crossterm::event::Event::Key(key));
+          vec![Action::Render]
+        },
+      },
+      Mode::LogSearch => match key.code {
+        KeyCode::Esc => {
+          self.log_search_input.reset();
+          vec![Action::EnterMode(Mode::ServiceList)]
+        },
+        KeyCode::Enter => {
+          if let Some(&first) = self.log_match_rows().first() {
+            self.logs_scroll_offset = first.min(u16::MAX as usize) as u16;
+          }
+          vec![Action::EnterMode(Mode::ServiceList)]
+        },
+        _ => {
+          self.log_search_input.handle_event(&crossterm::event::Event::Key(key));
+          vec![Action::Render]
+        },
+      },
+      Mode::LogTimeRange => match key.code {
+        KeyCode::Esc => vec![Action::EnterMode(Mode::ServiceList)],
+        KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+          self.editing_log_until = !self.editing_log_until;
+          vec![Action::Render]
+        },
+        KeyCode::Enter => {
+          let value = |input: &Input| Some(input.value().trim().to_string()).filter(|v| !v.is_empty());
+          vec![
+            Action::EnterMode(Mode::ServiceList),
+            Action::SetLogTimeRange { since: value(&self.log_since_input), until: value(&self.log_until_input) },
+          ]
+        },
+        _ => {
+          let input = if self.editing_log_until { &mut self.log_until_input } else { &mut self.log_since_input };
+          input.handle_event(&crossterm::event::Event::Key(key));
+          vec![Action::Render]
+        },
+      },
       Mode::ActionMenu => match key.code {
         KeyCode::Esc => vec![Action::EnterMode(Mode::ServiceList)],
         KeyCode::Down | KeyCode::Char('j') => {
@@ -544,10 +2218,20 @@ impl Component for Home {
           self.menu_items.previous();
           vec![Action::Render]
         },
-        KeyCode::Enter | KeyCode::Char(' ') => match self.menu_items.selected() {
-          Some(i) => vec![i.action.clone()],
+        KeyCode::Enter | KeyCode::Char(' ') => self.activate_menu_item(),
+        _ => vec![],
+      },
+      Mode::Confirm => match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => match self.pending_action.take() {
+          Some(item) => vec![item.action],
           None => vec![Action::EnterMode(Mode::ServiceList)],
         },
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+          // go back to the action menu (or wherever we came from) without rebuilding it, so the selection is preserved
+          self.pending_action = None;
+          self.mode = self.confirm_return_mode;
+          vec![Action::Render]
+        },
         _ => vec![],
       },
       Mode::Processing => match key.code {
@@ -558,42 +2242,124 @@ impl Component for Home {
   }
 
   fn dispatch(&mut self, action: Action) -> Option<Action> {
+    if is_service_action(&action) {
+      self.last_service_action = Some(action.clone());
+    }
+
     match action {
       Action::ToggleShowLogger => {
         self.show_logger = !self.show_logger;
         return Some(Action::Render);
       },
       Action::EnterMode(mode) => {
-        if mode == Mode::ActionMenu {
-          if let Some(selected) = self.filtered_units.selected() {
-            let mut menu_items = vec![
+        if mode == Mode::ActionMenu && !self.marked_units.is_empty() {
+          // only the operations that make sense in bulk
+          let units = self.marked_units.iter().cloned().sorted_by(|a, b| a.name.cmp(&b.name)).collect_vec();
+          let menu_items = [BatchOperation::Start, BatchOperation::Stop, BatchOperation::Restart]
+            .into_iter()
+            .map(|operation| {
+              let name = format!("{} {} marked units", operation.name(), units.len());
+              MenuItem::new(&name, Action::RunBatch(operation, units.clone()))
+            })
+            .collect_vec();
+          self.menu_items = StatefulList::with_items(menu_items);
+          self.menu_items.state.select(Some(0));
+        } else if mode == Mode::ActionMenu {
+          if let Some(selected) = self.selected_unit() {
+            let mut menu_items = vec![];
+            if self.edited_units.contains(&selected.id()) {
+              let action = Action::ApplyUnitChanges(selected.id());
+              menu_items.push(MenuItem::new("Apply changes (daemon-reload + restart)", action));
+            }
+            menu_items.extend([
               MenuItem::new("Start", Action::StartService(selected.id())),
               MenuItem::new("Stop", Action::StopService(selected.id())),
               MenuItem::new("Restart", Action::RestartService(selected.id())),
               MenuItem::new("Reload", Action::ReloadService(selected.id())),
-              // TODO add these
-              // MenuItem::new("Enable", Action::EnableService(selected.clone())),
-              // MenuItem::new("Disable", Action::DisableService(selected.clone())),
-            ];
+              MenuItem::new("Enable", Action::EnableService(selected.id())),
+              MenuItem::new("Disable", Action::DisableService(selected.id())),
+              MenuItem::new("Force stop (SIGKILL)", Action::ForceStopService(selected.id())),
+              MenuItem::new("Send signal…", Action::OpenSignalMenu(selected.id())),
+            ]);
 
-            if let Some(Ok(file_path)) = &selected.file_path {
-              menu_items.push(MenuItem::new("Copy unit file path to clipboard", Action::CopyUnitFilePath));
-              menu_items.push(MenuItem::new(
-                "Edit unit file",
-                Action::EditUnitFile { unit: selected.id(), path: file_path.clone() },
-              ));
+            if selected.is_failed() {
+              menu_items.push(MenuItem::new("Reset failed state", Action::ResetFailed(selected.id())));
             }
 
-            self.menu_items = StatefulList::with_items(menu_items);
-            self.menu_items.state.select(Some(0));
-          } else {
-            return None;
-          }
-        }
+            match selected.freezer_state.as_deref() {
+              Some("frozen" | "freezing") => menu_items.push(MenuItem::new("Thaw", Action::ThawService(selected.id()))),
+              Some("running") if selected.is_active() => {
+                menu_items.push(MenuItem::new("Freeze", Action::FreezeService(selected.id())))
+              },
+              _ => (),
+            }
+
+            if selected.is_masked() {
+              menu_items.push(MenuItem::new("Unmask", Action::UnmaskService(selected.id())));
+            } else {
+              menu_items.push(MenuItem::new("Mask", Action::MaskService(selected.id())));
+            }
+
+            menu_items.push(MenuItem::new("Copy details to clipboard", Action::CopyDetails));
+            menu_items.push(MenuItem::new("Copy unit name", Action::CopyUnitName));
+            menu_items.push(MenuItem::new("Copy invocation ID", Action::CopyInvocationId));
+            menu_items.push(MenuItem::new("Copy logs to clipboard", Action::CopyLogs));
+            menu_items.push(MenuItem::new("Export logs to file", Action::ExportLogs));
+            menu_items.push(MenuItem::new("View unit file", Action::ViewUnitFile));
+            menu_items.push(MenuItem::new("Show dependencies", Action::ShowDependencies));
+            menu_items.push(MenuItem::new("Show all properties", Action::ShowAllProperties));
+            let watch =
+              if self.watched_units.contains(&selected.id()) { "Stop watching" } else { "Watch for state changes" };
+            menu_items.push(MenuItem::new(watch, Action::ToggleWatch(selected.id())));
+
+            if let Some(Ok(_)) = &selected.file_path {
+              menu_items.push(MenuItem::new("Copy unit file path to clipboard", Action::CopyUnitFilePath));
+              menu_items.push(MenuItem::new("Open containing folder", Action::OpenUnitFileDirectory));
+            }
+            // always offered so it's discoverable, even if we couldn't find the file
+            let edit_action = match &selected.file_path {
+              _ if selected.is_transient() => Action::EnterError(format!(
+                "Can't edit {}: it's a transient unit, created at runtime (by systemd-run, for a scope, etc.) \
+                 rather than from a unit file on disk. It goes away once it stops",
+                selected.name
+              )),
+              Some(Ok(file_path)) => Action::EditUnitFile { unit: selected.id(), path: file_path.clone() },
+              Some(Err(e)) => Action::EnterError(format!("Can't edit {}: the unit file path {}", selected.name, e)),
+              None => Action::EnterError(format!(
+                "Can't edit {}: the unit file hasn't been located yet. Try again in a moment",
+                selected.name
+              )),
+            };
+            menu_items.push(MenuItem::new("Edit unit file", edit_action));
+            for path in selected.drop_in_paths.iter().flatten() {
+              let file_name = Path::new(path).file_name().map_or(path.as_str(), |n| n.to_str().unwrap_or(path));
+              let action = Action::EditUnitFile { unit: selected.id(), path: path.clone() };
+              menu_items.push(MenuItem::new(&format!("Edit drop-in {}", file_name), action));
+            }
+            if selected.name.ends_with(".service") {
+              menu_items.push(MenuItem::new("Show environment", Action::ShowEnvironment));
+            }
+            for (path, _) in selected.environment.iter().flat_map(|e| &e.files) {
+              let action = Action::EditUnitFile { unit: selected.id(), path: path.clone() };
+              menu_items.push(MenuItem::new(&format!("Edit environment file {}", path), action));
+            }
+
+            self.menu_items = StatefulList::with_items(menu_items);
+            self.menu_items.state.select(Some(0));
+          } else {
+            return None;
+          }
+        }
 
         self.mode = mode;
         return Some(Action::Render);
       },
+      Action::OfferRetry => {
+        self.retry_action = self.last_service_action.clone();
+      },
+      Action::OfferPrivilegedRetry(systemctl_args) => {
+        self.privileged_retry = Some(systemctl_args);
+      },
       Action::EnterError(err) => {
         tracing::error!(err);
         self.error_message = err;
@@ -603,13 +2369,14 @@ impl Component for Home {
         if self.mode != Mode::Help {
           self.previous_mode = Some(self.mode);
           self.mode = Mode::Help;
+          self.help_scroll_offset = 0;
         } else {
           self.mode = self.previous_mode.unwrap_or(Mode::Search);
         }
         return Some(Action::Render);
       },
       Action::CopyUnitFilePath => {
-        if let Some(selected) = self.filtered_units.selected() {
+        if let Some(selected) = self.selected_unit() {
           if let Some(Ok(file_path)) = &selected.file_path {
             match clipboard_anywhere::set_clipboard(file_path) {
               Ok(_) => return Some(Action::EnterMode(Mode::ServiceList)),
@@ -620,26 +2387,510 @@ impl Component for Home {
           }
         }
       },
+      Action::CopyUnitName => {
+        if let Some(selected) = self.selected_unit() {
+          match clipboard_anywhere::set_clipboard(&selected.name) {
+            Ok(_) => return Some(Action::EnterMode(Mode::ServiceList)),
+            Err(e) => return Some(Action::EnterError(format!("Error copying to clipboard: {}", e))),
+          }
+        }
+      },
+      Action::CopyInvocationId => {
+        if let Some(unit) = self.selected_service() {
+          let tx = self.action_tx.clone().unwrap();
+          tokio::spawn(async move {
+            let action = match systemd::get_invocation_id(&unit).await {
+              Ok(Some(id)) => match clipboard_anywhere::set_clipboard(&id) {
+                Ok(_) => Action::EnterMode(Mode::ServiceList),
+                Err(e) => Action::EnterError(format!("Error copying to clipboard: {}", e)),
+              },
+              Ok(None) => {
+                Action::EnterError(format!("{} has no invocation ID, it hasn't run since it was loaded", unit.name))
+              },
+              Err(e) => Action::EnterError(format!("Error getting the invocation ID of {}: {}", unit.name, e)),
+            };
+            let _ = tx.send(action);
+          });
+        }
+      },
+      Action::OpenUnitFileDirectory => {
+        let selected = self.selected_unit()?;
+        let Some(Ok(file_path)) = &selected.file_path else {
+          return Some(Action::EnterError("No unit file path available".into()));
+        };
+        let directory = Path::new(file_path).parent().unwrap_or(Path::new("/")).to_path_buf();
+        if let Some(host) = systemd::remote_host() {
+          return Some(Action::EnterError(format!(
+            "{} is on {}, it can't be opened in a file manager here",
+            directory.display(),
+            host
+          )));
+        }
+        // xdg-open on Linux, open on the odd system that only has that
+        let Some(opener) = ["xdg-open", "open"].into_iter().find(|p| crate::utils::find_in_path(p).is_some()) else {
+          return Some(Action::EnterError("Couldn't find xdg-open to open the folder with".into()));
+        };
+
+        // it hands off to a file manager and exits, no need to give up the terminal. Wait for it in the background
+        // so a failure (e.g. no file manager configured) still shows up
+        let tx = self.action_tx.clone().unwrap();
+        tokio::spawn(async move {
+          let status = tokio::process::Command::new(opener)
+            .arg(&directory)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await;
+          let error = match status {
+            Ok(status) if status.success() => return,
+            Ok(status) => format!("`{} {}` failed ({})", opener, directory.display(), status),
+            Err(e) => format!("Failed to run {}: {}", opener, e),
+          };
+          let _ = tx.send(Action::EnterError(error));
+        });
+        return Some(Action::EnterMode(Mode::ServiceList));
+      },
+      Action::ShowDependencies => {
+        if let Some(unit) = self.selected_service() {
+          let tx = self.action_tx.clone().unwrap();
+          tokio::spawn(async move {
+            match systemd::get_dependencies(&unit).await {
+              Ok(dependencies) => tx.send(Action::SetDependencies { unit, dependencies }).unwrap(),
+              Err(e) => {
+                tx.send(Action::EnterError(format!("Error getting dependencies of {}: {}", unit.name, e))).unwrap()
+              },
+            }
+          });
+        }
+      },
+      Action::SetDependencies { unit, dependencies } => {
+        let mut rows = vec![];
+        for (group, units) in dependencies {
+          rows.push(DependencyRow::Header(group));
+          rows.extend(units.into_iter().map(DependencyRow::Unit));
+        }
+        self.dependencies = StatefulList::with_items(rows);
+        self.dependencies_of = Some(unit);
+        self.select_dependency(true);
+        return Some(Action::EnterMode(Mode::Dependencies));
+      },
+      Action::ShowTimers => {
+        let tx = self.action_tx.clone().unwrap();
+        let scope = self.scope;
+        tokio::spawn(async move {
+          match systemd::get_timers(scope).await {
+            Ok(timers) => tx.send(Action::SetTimers(timers)).unwrap(),
+            Err(e) => tx.send(Action::EnterError(format!("Error getting timers: {}", e))).unwrap(),
+          }
+        });
+      },
+      Action::SetTimers(timers) => {
+        self.timers = StatefulList::with_items(timers);
+        self.timers.state.select(Some(0));
+        return Some(Action::EnterMode(Mode::Timers));
+      },
+      Action::UnitsLoadFailed(message) => {
+        self.units_loading = false;
+        self.startup_unit = None;
+        return Some(Action::EnterError(message));
+      },
+      Action::UnitsLoaded => {
+        self.units_loading = false;
+        // everything is loaded, if the last selected unit isn't here it's gone
+        self.restore_selection = None;
+        let Some(startup_unit) = self.startup_unit.take() else {
+          return Some(Action::Render);
+        };
+        // `nginx` is short for `nginx.service`, like systemctl
+        let unit = self
+          .all_units
+          .keys()
+          .find(|id| id.name == startup_unit.name)
+          .or_else(|| self.all_units.keys().find(|id| id.name == format!("{}.service", startup_unit.name)))
+          .cloned();
+        let Some(unit) = unit else {
+          return Some(Action::EnterError(format!(
+            "Couldn't find {} to select. {}",
+            startup_unit.name,
+            self.unit_not_loaded_reason()
+          )));
+        };
+
+        let tx = self.action_tx.clone().unwrap();
+        tx.send(Action::JumpToUnit(unit.clone())).unwrap();
+        if let Some(operation) = startup_unit.action {
+          // open the menu underneath, so saying no leaves the other actions a keypress away
+          tx.send(Action::EnterMode(Mode::ActionMenu)).unwrap();
+          let item = MenuItem::new(operation.name(), operation.unit_action(unit));
+          tx.send(Action::ConfirmAction(Box::new(item))).unwrap();
+        }
+        return Some(Action::Render);
+      },
+      Action::ConfirmAction(item) => {
+        self.pending_action = Some(*item);
+        self.confirm_return_mode = Mode::ActionMenu;
+        return Some(Action::EnterMode(Mode::Confirm));
+      },
+      Action::JumpToUnit(unit) => {
+        let find = |home: &Self| {
+          let unit_index = home.all_units.get_index_of(&unit)?;
+          home.filtered_units.items.iter().position(|&i| i == unit_index)
+        };
+        let mut index = find(self);
+        if index.is_none() {
+          // it might be hidden by the search, the state filter, or for being inactive or transient
+          self.input.reset();
+          self.state_filter = None;
+          self.hide_inactive = false;
+          self.hide_transient = false;
+          self.refresh_filtered_units();
+          index = find(self);
+        }
+        match index {
+          Some(index) => {
+            self.select(Some(index), true);
+            return Some(Action::EnterMode(Mode::ServiceList));
+          },
+          None => {
+            return Some(Action::EnterError(format!(
+              "{} isn't in the list of units. {}",
+              unit.name,
+              self.unit_not_loaded_reason()
+            )))
+          },
+        }
+      },
+      Action::ViewUnitFile => {
+        if let Some(selected) = self.selected_unit() {
+          match &selected.file_path {
+            Some(Ok(file_path)) => {
+              let tx = self.action_tx.clone().unwrap();
+              let file_path = file_path.clone();
+              tokio::spawn(async move {
+                let action = match systemd::read_file(&file_path).await {
+                  Ok(contents) => Action::ViewText { title: format!("─{}", file_path), contents },
+                  Err(e) => Action::EnterError(format!("Error reading {}: {}", file_path, e)),
+                };
+                let _ = tx.send(action);
+              });
+            },
+            _ => return Some(Action::EnterError("No unit file path available".into())),
+          }
+        }
+      },
+      Action::ShowEnvironment => {
+        if let Some(unit) = self.selected_service() {
+          let tx = self.action_tx.clone().unwrap();
+          tokio::spawn(async move {
+            let environment = match systemd::get_environment(&unit).await {
+              Ok(environment) => environment,
+              Err(e) => {
+                let _ = tx.send(Action::EnterError(format!("Error getting the environment of {}: {}", unit.name, e)));
+                return;
+              },
+            };
+            let contents = environment_text(&environment).await;
+            let title = format!("─Environment of {}", unit.name);
+            let _ = tx.send(Action::SetEnvironment { unit, environment });
+            let _ = tx.send(Action::ViewText { title, contents });
+          });
+        }
+      },
+      Action::ViewText { title, contents } => return Some(self.show_text(Some(title), contents)),
+      Action::ShowAllProperties => {
+        if let Some(unit) = self.selected_service() {
+          let tx = self.action_tx.clone().unwrap();
+          tokio::spawn(async move {
+            let action = match systemd::get_all_properties(&unit).await {
+              Ok(contents) => Action::ViewText { title: format!("─All properties of {}", unit.name), contents },
+              Err(e) => Action::EnterError(format!("Error getting the properties of {}: {}", unit.name, e)),
+            };
+            let _ = tx.send(action);
+          });
+        }
+      },
+      Action::CopyLogs => {
+        // take the newest lines that fit under the cap
+        let mut size = 0;
+        let lines = self
+          .logs
+          .iter()
+          .rev()
+          .take_while(|l| {
+            size += l.len() + 1;
+            size <= MAX_CLIPBOARD_LOG_BYTES
+          })
+          .collect_vec();
+        if lines.len() < self.logs.len() {
+          warn!(
+            "Logs are too big for the clipboard, only copying the last {} of {} lines",
+            lines.len(),
+            self.logs.len()
+          );
+        }
+        let text = lines.into_iter().rev().map(|l| &l.text).join("\n");
+        match clipboard_anywhere::set_clipboard(&text) {
+          Ok(_) => return Some(Action::EnterMode(Mode::ServiceList)),
+          Err(e) => return Some(Action::EnterError(format!("Error copying to clipboard: {}", e))),
+        }
+      },
+      Action::ExportLogs => {
+        if let Some(selected) = self.selected_unit() {
+          match export_logs(&selected.name, &self.logs) {
+            Ok(path) => {
+              self.success_message = format!("Logs written to {}", path.display());
+              return Some(Action::EnterMode(Mode::Success));
+            },
+            Err(e) => return Some(Action::EnterError(format!("Error exporting logs: {:#}", e))),
+          }
+        }
+      },
+      Action::SetLogBoot(boot) => {
+        self.log_boot = boot;
+        self.logs = vec![];
+        self.logs_scroll_offset = 0;
+        self.get_logs();
+        return Some(Action::Render);
+      },
+      Action::SetLogRange(range) => {
+        self.log_range = range;
+        self.logs = vec![];
+        self.logs_scroll_offset = 0;
+        self.get_logs();
+        return Some(Action::Render);
+      },
+      Action::SetLogTimeRange { since, until } => {
+        self.log_since = since;
+        self.log_until = until;
+        self.logs = vec![];
+        self.logs_scroll_offset = 0;
+        self.get_logs();
+        return Some(Action::Render);
+      },
+      Action::SetLogPriority(priority) => {
+        self.log_priority = priority;
+        self.logs = vec![];
+        self.logs_scroll_offset = 0;
+        self.get_logs();
+        return Some(Action::Render);
+      },
+      Action::SetLogLines(lines) => {
+        self.log_lines = lines.clamp(MIN_LOG_LINES, MAX_LOG_LINES);
+        info!("Fetching {} log lines", self.log_lines);
+        self.get_logs();
+        return Some(Action::Render);
+      },
+      Action::SetScope(scope) => {
+        self.scope = scope;
+        let previously_selected = self.selected_service();
+        self.all_units.retain(|_, u| scope.includes(u.scope));
+        self.refilter_units(previously_selected);
+        return Some(Action::RefreshServices);
+      },
+      Action::SetStateFilter(filter) => {
+        self.state_filter = filter;
+        self.refresh_filtered_units();
+        return Some(Action::Render);
+      },
+      Action::CycleSortMode => {
+        self.sort_mode = self.sort_mode.next();
+        self.refresh_filtered_units();
+        return Some(Action::Render);
+      },
+      Action::ToggleLogColors => {
+        self.colorize_logs = !self.colorize_logs;
+        return Some(Action::Render);
+      },
+      Action::ToggleStructuredLogs => {
+        self.structured_logs = !self.structured_logs;
+        self.logs = vec![];
+        self.get_logs();
+        self.logs_scroll_offset = 0;
+        return Some(Action::Render);
+      },
+      Action::ToggleLogsPaused => {
+        self.logs_paused = !self.logs_paused;
+        if !self.logs_paused {
+          let held_back = std::mem::take(&mut self.paused_log_lines);
+          // same as if they'd come in one at a time: a scrolled-away view stays on what it was showing
+          if self.logs_scroll_offset > 0 {
+            let rows: usize = visible_logs(&held_back, self.log_grep.as_ref()).map(|l| self.log_line_height(l)).sum();
+            self.logs_scroll_offset = self.logs_scroll_offset.saturating_add(rows.min(u16::MAX as usize) as u16);
+          }
+          self.logs.extend(held_back);
+        }
+        return Some(Action::Render);
+      },
+      Action::ToggleRelativeLogTimes => {
+        self.relative_log_times = !self.relative_log_times;
+        return Some(Action::Render);
+      },
+      Action::SetLogGrep(grep) => {
+        if grep.is_none() {
+          self.log_grep_input.reset();
+        }
+        self.log_grep = grep;
+        // the lines on screen are a different set now
+        self.logs_scroll_offset = 0;
+        return Some(Action::Render);
+      },
+      Action::ToggleWatch(unit) => {
+        if !self.watched_units.remove(&unit) {
+          self.watched_units.insert(unit);
+        }
+        return Some(Action::Render);
+      },
+      Action::ToggleHideInactive => {
+        self.hide_inactive = !self.hide_inactive;
+        self.refresh_filtered_units();
+        return Some(Action::Render);
+      },
+      Action::ToggleHideTransient => {
+        self.hide_transient = !self.hide_transient;
+        self.refresh_filtered_units();
+        return Some(Action::Render);
+      },
+      Action::ToggleGroupByType => {
+        self.group_by_type = !self.group_by_type;
+        self.refresh_filtered_units();
+        return Some(Action::Render);
+      },
+      Action::ToggleLogPreviews => {
+        self.show_log_previews = !self.show_log_previews;
+        // previews go stale quickly, start fresh every time they're turned on
+        self.log_previews.clear();
+        self.log_previews_requested.clear();
+        return Some(Action::Render);
+      },
+      Action::SetLogPreview { unit, line } => {
+        self.log_previews.insert(unit, line);
+      },
+      Action::CopyDetails => {
+        if let Some(selected) = self.selected_unit() {
+          let details = details_as_text(selected);
+          match clipboard_anywhere::set_clipboard(&details) {
+            Ok(_) => return Some(Action::EnterMode(Mode::ServiceList)),
+            Err(e) => return Some(Action::EnterError(format!("Error copying to clipboard: {}", e))),
+          }
+        }
+      },
       Action::SetUnitFilePath { unit, path } => {
         if let Some(unit) = self.all_units.get_mut(&unit) {
           unit.file_path = Some(path.clone());
         }
-        self.refresh_filtered_units(); // copy the updated unit file path to the filtered list
+      },
+      Action::SetTimerTimes { unit, next_elapse, last_trigger } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.next_elapse = next_elapse;
+          unit.last_trigger = last_trigger;
+        }
+      },
+      Action::SetStateChangeTimes { unit, active_enter, inactive_enter } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.active_enter = active_enter;
+          unit.inactive_enter = inactive_enter;
+        }
+      },
+      Action::SetEnablementState { unit, state } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.enablement_state = Some(state);
+        }
+      },
+      Action::SetSocketInfo { unit, info } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.socket_info = Some(info);
+        }
+      },
+      Action::RefreshDetails => {
+        // whatever just happened (enable, mask...) may have changed these
+        if let Some(selected) = self.selected_service() {
+          self.invalidate_unit_cache(Some(&selected));
+        }
+        self.get_logs();
+      },
+      Action::InvalidateUnitCache(unit) => self.invalidate_unit_cache(unit.as_ref()),
+      Action::SetMainPid { unit, pid } => {
+        // the selection may have moved on while we were waiting on D-Bus
+        if self.selected_service().as_ref() != Some(&unit) {
+          return None;
+        }
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.main_pid = Some(pid);
+          unit.main_process_name = match pid {
+            0 => None,
+            // the process is on the other machine
+            _ if systemd::remote_host().is_some() => None,
+            pid => std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok().map(|comm| comm.trim().to_string()),
+          };
+        }
+      },
+      Action::SetRestartCount { unit, n_restarts } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.n_restarts = Some(n_restarts);
+        }
+      },
+      Action::SetResourceUsage { unit, memory_current, cpu_usage } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.memory_current = memory_current;
+          unit.cpu_usage = cpu_usage;
+        }
       },
       Action::SetLogs { unit, logs } => {
-        if let Some(selected) = self.filtered_units.selected() {
+        if let Some(selected) = self.selected_unit() {
           if selected.id() == unit {
             self.logs = logs;
+            self.logs_loading = false;
+            self.log_cursor = 0;
           }
         }
       },
       Action::AppendLogLine { unit, line } => {
-        if let Some(selected) = self.filtered_units.selected() {
-          if selected.id() == unit {
+        if let Some(selected) = self.selected_unit() {
+          if selected.id() == unit && self.logs_paused {
+            self.paused_log_lines.push(line);
+          } else if selected.id() == unit {
+            let visible = self.log_grep.as_ref().is_none_or(|grep| grep.is_match(&line));
+            // Logs are shown newest first. At the top (Home) we're following, and new lines show up in view.
+            // If the user has scrolled away to read something, keep those lines where they are instead
+            if visible && self.logs_scroll_offset > 0 {
+              let rows = self.log_line_height(&line).min(u16::MAX as usize) as u16;
+              self.logs_scroll_offset = self.logs_scroll_offset.saturating_add(rows);
+            }
             self.logs.push(line);
+            // the cursor stays on the line it was on
+            if visible && self.mode == Mode::LogLines {
+              self.log_cursor += 1;
+            }
           }
         }
       },
+      Action::ScrollUp(offset) if self.mode == Mode::UnitFile => {
+        self.unit_file_scroll_offset = self.unit_file_scroll_offset.saturating_sub(offset);
+      },
+      Action::ScrollDown(offset) if self.mode == Mode::UnitFile => {
+        self.unit_file_scroll_offset = self.unit_file_scroll_offset.saturating_add(offset);
+      },
+      Action::ScrollToTop if self.mode == Mode::UnitFile => {
+        self.unit_file_scroll_offset = 0;
+      },
+      Action::ScrollToBottom if self.mode == Mode::UnitFile => {
+        self.unit_file_scroll_offset = self.unit_file_contents.lines().count() as u16;
+      },
+      // in the logs pane the cursor moves, and the view follows it when rendered
+      Action::ScrollUp(offset) if self.mode == Mode::LogLines => {
+        self.log_cursor = self.log_cursor.saturating_sub(offset as usize);
+      },
+      Action::ScrollDown(offset) if self.mode == Mode::LogLines => {
+        self.log_cursor = self.log_cursor.saturating_add(offset as usize);
+      },
+      Action::ScrollToTop if self.mode == Mode::LogLines => {
+        self.log_cursor = 0;
+      },
+      Action::ScrollToBottom if self.mode == Mode::LogLines => {
+        // clamped to the last line when rendered
+        self.log_cursor = usize::MAX;
+      },
       Action::ScrollUp(offset) => {
         self.logs_scroll_offset = self.logs_scroll_offset.saturating_sub(offset);
         info!("scroll offset: {}", self.logs_scroll_offset);
@@ -652,30 +2903,122 @@ impl Component for Home {
         self.logs_scroll_offset = 0;
       },
       Action::ScrollToBottom => {
-        // TODO: this is partially broken, figure out a better way to scroll to end
-        // problem: we don't actually know the height of the paragraph before it's rendered
-        // because it's wrapped based on the width of the widget
-        // A proper fix might need to wait until ratatui improves scrolling: https://github.com/ratatui-org/ratatui/issues/174
-        self.logs_scroll_offset = self.logs.len() as u16;
+        // we don't know how the logs wrap until they're rendered, so overshoot and let render clamp to the last page
+        self.logs_scroll_offset = u16::MAX;
       },
 
       Action::StartService(service_name) => self.start_service(service_name),
+      Action::RunBatch(operation, units) => {
+        self.marked_units.clear();
+        self.batch_action(operation, units);
+      },
+      Action::SetProcessingStatus(status) => {
+        self.processing_status = Some(status);
+        return Some(Action::Render);
+      },
+      Action::SetBatchProgress(done, total) => {
+        self.batch_progress = Some((done, total));
+        return Some(Action::Render);
+      },
       Action::StopService(service_name) => self.stop_service(service_name),
       Action::ReloadService(service_name) => self.reload_service(service_name),
+      Action::DaemonReload => self.daemon_reload(),
+      Action::UnitFileEdited(unit) => {
+        self.edited_units.insert(unit);
+      },
+      Action::ApplyUnitChanges(unit) => self.apply_unit_changes(unit),
       Action::RestartService(service_name) => self.restart_service(service_name),
+      Action::EnableService(service_name) => self.enable_service(service_name),
+      Action::DisableService(service_name) => self.disable_service(service_name),
+      Action::MaskService(service_name) => self.mask_service(service_name),
+      Action::ResetFailed(service_name) => self.reset_failed(service_name),
+      Action::FreezeService(service_name) => self.freeze_service(service_name),
+      Action::ThawService(service_name) => self.thaw_service(service_name),
+      Action::SetTransient { unit, transient } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.transient = Some(transient);
+        }
+      },
+      Action::SetDropInPaths { unit, paths } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.drop_in_paths = Some(paths);
+        }
+      },
+      Action::SetEnvironment { unit, environment } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.environment = Some(environment);
+        }
+      },
+      Action::SetFreezerState { unit, state } => {
+        if let Some(unit) = self.all_units.get_mut(&unit) {
+          unit.freezer_state = Some(state);
+        }
+      },
+      Action::UnmaskService(service_name) => self.unmask_service(service_name),
+      Action::ForceStopService(service_name) => self.force_stop_service(service_name),
+      Action::OpenSignalMenu(unit) => {
+        // swap the action menu's contents for a list of signals to send to the main process
+        let signals = [
+          ("SIGTERM", libc::SIGTERM),
+          ("SIGKILL", libc::SIGKILL),
+          ("SIGHUP", libc::SIGHUP),
+          ("SIGUSR1", libc::SIGUSR1),
+        ];
+        let menu_items = signals
+          .into_iter()
+          .map(|(name, signal)| MenuItem::new(name, Action::KillService { unit: unit.clone(), signal }))
+          .collect_vec();
+        self.menu_items = StatefulList::with_items(menu_items);
+        self.menu_items.state.select(Some(0));
+        return Some(Action::Render);
+      },
+      Action::KillService { unit, signal } => self.kill_service(unit, signal),
       Action::RefreshServices => {
+        // service actions ask for several refreshes on top of the regular ones, and they can pile up.
+        // Only run one fetch at a time; anything asked for meanwhile becomes a single fetch afterwards
+        if self.refreshing_services {
+          self.refresh_queued = true;
+          return None;
+        }
+        self.refreshing_services = true;
+
         let tx = self.action_tx.clone().unwrap();
         let scope = self.scope;
         let limit_units = self.limit_units.to_vec();
         tokio::spawn(async move {
-          let units = systemd::get_all_services(scope, &limit_units)
-            .await
-            .expect("Failed to get services. Check that systemd is running and try running this tool with sudo.");
-          tx.send(Action::SetServices(units)).unwrap();
+          match systemd::get_all_services(scope, &limit_units).await {
+            Ok(units) => {
+              // without a user bus we only heard about system units, that doesn't mean the user units are gone
+              let scopes = [UnitScope::Global, UnitScope::User]
+                .into_iter()
+                .filter(|&s| scope.includes(s))
+                .filter(|&s| {
+                  s == UnitScope::Global || !matches!(scope, Scope::All) || systemd::user_units_error().is_none()
+                })
+                .collect();
+              tx.send(Action::SyncServices { scopes, units }).unwrap()
+            },
+            Err(e) => error!("Failed to refresh services: {:#}", e),
+          }
+          tx.send(Action::RefreshServicesFinished).unwrap();
         });
       },
-      Action::SetServices(units) => {
-        self.update_units(units);
+      Action::RefreshServicesFinished => {
+        self.refreshing_services = false;
+        if std::mem::take(&mut self.refresh_queued) {
+          return Some(Action::RefreshServices);
+        }
+      },
+      Action::SetServices(mut units) => {
+        // a refresh for the previous scope may still have been in flight when the scope changed
+        units.retain(|u| self.scope.includes(u.scope));
+        self.update_units(units, &[]);
+        return Some(Action::Render);
+      },
+      Action::SyncServices { mut scopes, mut units } => {
+        units.retain(|u| self.scope.includes(u.scope));
+        scopes.retain(|&s| self.scope.includes(s));
+        self.update_units(units, &scopes);
         return Some(Action::Render);
       },
       Action::SpinnerTick => {
@@ -696,7 +3039,11 @@ impl Component for Home {
 
   fn render(&mut self, f: &mut Frame<'_>, rect: Rect) {
     let rect = if self.show_logger {
-      let chunks = Layout::new(Direction::Vertical, Constraint::from_percentages([50, 50])).split(rect);
+      let chunks = Layout::new(
+        Direction::Vertical,
+        Constraint::from_percentages([100 - self.logger_percent, self.logger_percent]),
+      )
+      .split(rect);
 
       self.logger.render(f, chunks[1]);
       chunks[0]
@@ -704,13 +3051,12 @@ impl Component for Home {
       rect
     };
 
-    let rects = Layout::new(Direction::Vertical, [Constraint::Min(3), Constraint::Percentage(100)]).split(rect);
+    let rects =
+      Layout::new(Direction::Vertical, [Constraint::Min(3), Constraint::Percentage(100), Constraint::Length(1)])
+        .split(rect);
     let search_panel = rects[0];
     let main_panel = rects[1];
-
-    fn colored_line(value: &str, color: Color) -> Line {
-      Line::from(vec![Span::styled(value, Style::default().fg(color))])
-    }
+    let status_line = rects[2];
 
     // Helper for colouring based on the same logic as sysz
     // https://github.com/joehillen/sysz/blob/8da8e0dcbfde8d68fbdb22382671e395bd370d69/sysz#L69C1-L72C24
@@ -718,28 +3064,97 @@ impl Component for Home {
     //    green       active
     //    red         failed
     //    yellow      not-found
-    fn unit_color(unit: &UnitWithStatus) -> Color {
+    fn unit_color(unit: &UnitWithStatus, colors: &Colors) -> Color {
       if unit.is_active() {
-        Color::Green
+        colors.active
       } else if unit.is_failed() {
-        Color::Red
+        colors.failed
       } else if unit.is_not_found() {
-        Color::Yellow
+        colors.not_found
       } else {
         Color::Reset
       }
     }
 
-    let items: Vec<ListItem> = self
-      .filtered_units
-      .items
-      .iter()
-      .map(|i| {
-        let color = unit_color(i);
-        let line = colored_line(i.short_name(), color);
-        ListItem::new(line)
-      })
-      .collect();
+    // make room for log previews on wide terminals
+    let list_constraint =
+      if self.show_log_previews && main_panel.width >= 120 { Constraint::Percentage(50) } else { Constraint::Min(30) };
+    let chunks = Layout::new(Direction::Horizontal, [list_constraint, Constraint::Percentage(100)]).split(main_panel);
+    // room for a unit's name inside the borders, and the checkmark column if it's showing
+    let mark_width = if self.marked_units.is_empty() { 0 } else { 2 };
+    let name_width = (chunks[0].width.saturating_sub(2) as usize).saturating_sub(mark_width);
+
+    let mut selected_truncated = false;
+
+    let mut items: Vec<ListItem> = vec![];
+    self.services_list_rows.clear();
+    let mut current_group = None;
+    for (index, &unit_index) in self.filtered_units.items.iter().enumerate() {
+      let i = &self.all_units[unit_index];
+      if self.group_by_type {
+        // units are already sorted by group, so a header goes wherever the group changes. Empty groups never show up
+        let (_, group) = unit_group(i);
+        if current_group != Some(group) {
+          current_group = Some(group);
+          let header = Span::styled(
+            format!("── {} ──", group),
+            Style::default().fg(self.config.colors.accent).add_modifier(Modifier::BOLD),
+          );
+          items.push(ListItem::new(header));
+          self.services_list_rows.push(None);
+        }
+      }
+
+      let style = Style::default().fg(unit_color(i, &self.config.colors));
+      let match_indices = self.search_match_indices.get(&unit_index).map(Vec::as_slice).unwrap_or_default();
+      // flag units that look like they're flapping
+      let flapping = i
+        .n_restarts
+        .filter(|&n| n >= FLAPPING_RESTART_COUNT)
+        .map(|n_restarts| Span::styled(format!(" ↻{}", n_restarts), Style::default().fg(self.config.colors.error)));
+      let watched = self
+        .watched_units
+        .contains(&i.id())
+        .then(|| Span::styled(" ◉", Style::default().fg(self.config.colors.accent)));
+      // cut long names short instead of letting the list clip them, keeping room for the markers.
+      // The full name goes in the details panel
+      let available = name_width
+        .saturating_sub(flapping.as_ref().map_or(0, |span| span.width()))
+        .saturating_sub(watched.as_ref().map_or(0, |span| span.width()));
+      let mut line = if i.short_name().width() > available {
+        selected_truncated |= self.filtered_units.state.selected() == Some(index);
+        let truncated = truncate_with_ellipsis(i.short_name(), available);
+        let spans = highlight_chars(&truncated, match_indices, style)
+          .into_iter()
+          .map(|span| Span::styled(span.content.into_owned(), span.style))
+          .collect_vec();
+        Line::from(spans)
+      } else {
+        Line::from(highlight_chars(i.short_name(), match_indices, style))
+      };
+      if !self.marked_units.is_empty() {
+        let mark = if self.marked_units.contains(&i.id()) { "✓ " } else { "  " };
+        line.spans.insert(0, Span::styled(mark, Style::default().fg(self.config.colors.border)));
+      }
+      if let Some(watched) = watched {
+        line.push_span(watched);
+      }
+      if let Some(flapping) = flapping {
+        line.push_span(flapping);
+      }
+      if self.show_log_previews {
+        if let Some(preview) = self.log_previews.get(&i.id()) {
+          line.push_span(Span::styled(format!("  {}", preview), Style::default().fg(Color::DarkGray)));
+        }
+      }
+      items.push(ListItem::new(line));
+      self.services_list_rows.push(Some(index));
+    }
+
+    if items.is_empty() && self.units_loading {
+      items.push(ListItem::new(Span::styled("Loading units…", Style::default().fg(Color::DarkGray))));
+      self.services_list_rows.push(None);
+    }
 
     // Create a List from all list items and highlight the currently selected one
     let items = List::new(items)
@@ -748,85 +3163,105 @@ impl Component for Home {
           .borders(Borders::ALL)
           .border_type(BorderType::Rounded)
           .border_style(if self.mode == Mode::ServiceList {
-            Style::default().fg(Color::LightGreen)
+            Style::default().fg(self.config.colors.border)
           } else {
             Style::default()
           })
-          .title("─Services"),
+          .title({
+            let mut title = format!("─{} Services", self.scope.name());
+            if let Some(host) = systemd::remote_host() {
+              title.push_str(&format!(" on {}", host));
+            }
+            if let Some(filter) = self.state_filter {
+              title.push_str(&format!(" ({})", filter.name()));
+            }
+            if self.sort_mode != SortMode::Name {
+              title.push_str(&format!(" ({})", self.sort_mode.name()));
+            }
+            if self.hide_inactive {
+              title.push_str(" (inactive hidden)");
+            }
+            if self.hide_transient {
+              title.push_str(" (transient hidden)");
+            }
+            if self.group_by_type {
+              title.push_str(" (by type)");
+            }
+            title
+          }),
       )
       .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
 
-    let chunks =
-      Layout::new(Direction::Horizontal, [Constraint::Min(30), Constraint::Percentage(100)]).split(main_panel);
     let right_panel = chunks[1];
 
-    f.render_stateful_widget(items, chunks[0], &mut self.filtered_units.state);
+    let visible_rows = chunks[0].height.saturating_sub(2) as usize;
+    let visible_units = if self.group_by_type {
+      // the grouped view has its own list state, since header rows shift every unit down
+      let selected_row = self
+        .filtered_units
+        .state
+        .selected()
+        .and_then(|selected| self.services_list_rows.iter().position(|row| *row == Some(selected)));
+      self.grouped_list_state.select(selected_row);
+      // keep a group's header in view when scrolling up to its first unit
+      if let Some(row) = selected_row {
+        if row > 0 && self.services_list_rows[row - 1].is_none() && self.grouped_list_state.offset() >= row {
+          *self.grouped_list_state.offset_mut() = row - 1;
+        }
+      }
+      f.render_stateful_widget(items, chunks[0], &mut self.grouped_list_state);
+
+      let rows = self.services_list_rows.iter().skip(self.grouped_list_state.offset()).take(visible_rows);
+      let units = rows.flatten().copied().collect_vec();
+      units.first().copied().unwrap_or(0)..units.last().map_or(0, |last| last + 1)
+    } else {
+      f.render_stateful_widget(items, chunks[0], &mut self.filtered_units.state);
+      let offset = self.filtered_units.state.offset();
+      offset..offset + visible_rows
+    };
+    self.services_list_area = chunks[0];
+
+    if self.show_log_previews {
+      // now that the list has been scrolled into place, fetch previews for whatever's visible
+      self.request_log_previews(visible_units);
+    }
 
-    let selected_item = self.filtered_units.selected();
+    let selected_item = self.selected_unit();
+
+    // the details panel is split into a right-aligned pane for property names and a pane for their values
+    let props_width = 14;
+    let values_width = right_panel.width.saturating_sub(2 + props_width) as usize;
+
+    let mut props_lines = vec![];
+    let mut values_lines = vec![];
+    if let Some(i) = selected_item {
+      let mut details = unit_details(i, &self.config.colors);
+      if selected_truncated {
+        details.insert(0, ("Name", Line::from(i.name.clone())));
+      }
+      for (label, value) in details {
+        let wrapped = wrap_line(value, values_width, MAX_DETAIL_LINES);
+        props_lines.push(Line::from(if label.is_empty() { String::new() } else { format!("{}: ", label) }));
+        props_lines.extend(std::iter::repeat_n(Line::from(""), wrapped.len().saturating_sub(1)));
+        values_lines.extend(wrapped);
+      }
+    }
 
+    let details_height = (values_lines.len() as u16).max(5) + 2;
     let right_panel =
-      Layout::new(Direction::Vertical, [Constraint::Min(7), Constraint::Percentage(100)]).split(right_panel);
+      Layout::new(Direction::Vertical, [Constraint::Length(details_height), Constraint::Percentage(100)])
+        .split(right_panel);
     let details_panel = right_panel[0];
     let logs_panel = right_panel[1];
 
     let details_block = Block::default().title("─Details").borders(Borders::ALL).border_type(BorderType::Rounded);
-    let details_panel_panes = Layout::new(Direction::Horizontal, [Constraint::Min(14), Constraint::Percentage(100)])
-      .split(details_block.inner(details_panel));
+    let details_panel_panes =
+      Layout::new(Direction::Horizontal, [Constraint::Min(props_width), Constraint::Percentage(100)])
+        .split(details_block.inner(details_panel));
     let props_pane = details_panel_panes[0];
     let values_pane = details_panel_panes[1];
 
-    let props_lines = vec![
-      Line::from("Description: "),
-      Line::from("Scope: "),
-      Line::from("Loaded: "),
-      Line::from("Active: "),
-      Line::from("Unit file: "),
-    ];
-
-    let details_text = if let Some(i) = selected_item {
-      fn line_color_string<'a>(value: String, color: Color) -> Line<'a> {
-        Line::from(vec![Span::styled(value, Style::default().fg(color))])
-      }
-
-      let load_color = match i.load_state.as_str() {
-        "loaded" => Color::Green,
-        "not-found" => Color::Yellow,
-        "error" => Color::Red,
-        _ => Color::Reset,
-      };
-
-      let active_color = match i.activation_state.as_str() {
-        "active" => Color::Green,
-        "inactive" => Color::Gray,
-        "failed" => Color::Red,
-        _ => Color::Reset,
-      };
-
-      let active_state_value = format!("{} ({})", i.activation_state, i.sub_state);
-
-      let scope = match i.scope {
-        UnitScope::Global => "Global",
-        UnitScope::User => "User",
-      };
-
-      let lines = vec![
-        colored_line(&i.description, Color::Reset),
-        colored_line(scope, Color::Reset),
-        colored_line(&i.load_state, load_color),
-        line_color_string(active_state_value, active_color),
-        match &i.file_path {
-          Some(Ok(file_path)) => Line::from(file_path.as_str()),
-          Some(Err(e)) => colored_line(e, Color::Red),
-          None => Line::from(""),
-        },
-      ];
-
-      lines
-    } else {
-      vec![]
-    };
-
-    let paragraph = Paragraph::new(details_text).style(Style::default());
+    let paragraph = Paragraph::new(values_lines).style(Style::default());
 
     let props_widget = Paragraph::new(props_lines).alignment(ratatui::layout::Alignment::Right);
     f.render_widget(props_widget, props_pane);
@@ -834,36 +3269,212 @@ impl Component for Home {
     f.render_widget(paragraph, values_pane);
     f.render_widget(details_block, details_panel);
 
-    let log_lines = self
-      .logs
-      .iter()
-      .rev()
-      .map(|l| {
-        if let Some((date, rest)) = l.splitn(2, ' ').collect_tuple() {
-          if date.len() != 24 {
-            return Line::from(l.as_str());
-          }
-          Line::from(vec![Span::styled(date, Style::default().fg(Color::DarkGray)), Span::raw(" "), Span::raw(rest)])
-        } else {
-          Line::from(l.as_str())
+    let log_query = self.log_search_input.value();
+    let log_cursor = (self.mode == Mode::LogLines).then_some(self.log_cursor);
+    let log_lines = visible_logs(&self.logs, self.log_grep.as_ref())
+      .enumerate()
+      .map(|(i, l)| {
+        let line = self.styled_log_line(l, log_query);
+        if log_cursor != Some(i) {
+          return line;
         }
+        // dates are dark gray too, lighten them so they don't disappear into the cursor
+        let spans = line
+          .spans
+          .into_iter()
+          .map(|span| match span.style.fg {
+            Some(Color::DarkGray) => Span::styled(span.content, span.style.fg(Color::Gray)),
+            _ => span,
+          })
+          .collect_vec();
+        Line::from(spans).patch_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
       })
       .collect_vec();
 
-    let paragraph = Paragraph::new(log_lines)
-      .block(Block::default().title("─Service Logs").borders(Borders::ALL).border_type(BorderType::Rounded))
-      .style(Style::default())
-      .wrap(Wrap { trim: true })
-      .scroll((self.logs_scroll_offset, 0));
-    f.render_widget(paragraph, logs_panel);
-
-    let width = search_panel.width.max(3) - 3; // keep 2 for borders and 1 for cursor
-    let scroll = self.input.visual_scroll(width as usize);
-    let input = Paragraph::new(self.input.value())
-      .style(match self.mode {
-        Mode::Search => Style::default().fg(Color::LightGreen),
-        _ => Style::default(),
-      })
+    let mut logs_title = vec![Span::raw("─Service Logs")];
+    if self.mode == Mode::LogLines {
+      logs_title.push(Span::styled(" (y: copy, Enter: expand, Esc: back)", Style::default().fg(Color::DarkGray)));
+    }
+    if self.logs_loading {
+      let spinner_char = SPINNER_CHARS[self.spinner_tick as usize % SPINNER_CHARS.len()];
+      logs_title.push(Span::styled(format!(" {}", spinner_char), Style::default().fg(self.config.colors.border)));
+    }
+    if self.structured_logs {
+      logs_title.push(Span::styled(" (structured)", Style::default().fg(Color::DarkGray)));
+    }
+    if self.logs_paused {
+      logs_title.push(Span::styled(
+        format!(" PAUSED ({} new)", self.paused_log_lines.len()),
+        Style::default().fg(self.config.colors.error).add_modifier(Modifier::BOLD),
+      ));
+    }
+    if let Some(matcher) = selected_item.and_then(|u| self.config.journal_matchers.get(&u.name)) {
+      logs_title.push(Span::styled(format!(" (+{})", matcher), Style::default().fg(self.config.colors.accent)));
+    }
+    match self.log_boot {
+      Some(0) => logs_title.push(Span::styled(" (current boot)", Style::default().fg(self.config.colors.info))),
+      Some(boot) => {
+        logs_title.push(Span::styled(format!(" (boot {})", boot), Style::default().fg(self.config.colors.info)))
+      },
+      None => (),
+    }
+    if self.log_since.is_some() || self.log_until.is_some() {
+      let since = self.log_since.as_deref().unwrap_or("start");
+      let until = self.log_until.as_deref().unwrap_or("now");
+      logs_title.push(Span::styled(
+        format!(" ({} to {}, not following)", since, until),
+        Style::default().fg(self.config.colors.info),
+      ));
+    } else if !self.config.follow_logs {
+      logs_title.push(Span::styled(" (not following)", Style::default().fg(Color::DarkGray)));
+    }
+    if let Some(priority) = self.log_priority {
+      logs_title.push(Span::styled(
+        format!(" ({} and above)", LOG_PRIORITIES[priority as usize]),
+        Style::default().fg(self.config.colors.info),
+      ));
+    }
+    match self.log_range {
+      LogRange::Tail if self.log_lines != self.config.log_lines => {
+        logs_title.push(Span::styled(format!(" (last {} lines)", self.log_lines), Style::default().fg(Color::DarkGray)))
+      },
+      LogRange::Tail => (),
+      range if self.logs_loading => logs_title.push(Span::styled(
+        format!(" ({}: loading, this can take a while...)", range.name()),
+        Style::default().fg(self.config.colors.warning),
+      )),
+      range => logs_title.push(Span::styled(
+        format!(" ({}: {} lines)", range.name(), self.logs.len()),
+        Style::default().fg(self.config.colors.info),
+      )),
+    }
+    if let Some(grep) = &self.log_grep {
+      let grep = match grep {
+        LogGrep::Text(text) => format!(" (grep: {})", text),
+        LogGrep::Regex(regex) => format!(" (grep: /{}/)", regex.as_str()),
+      };
+      logs_title.push(Span::styled(grep, Style::default().fg(self.config.colors.warning)));
+    }
+    if !log_query.is_empty() {
+      let match_count = self.log_match_indices().len();
+      logs_title.push(Span::styled(
+        format!(" (/{}: {} matching lines)", log_query, match_count),
+        Style::default().fg(self.config.colors.warning),
+      ));
+    }
+
+    let logs_border = match self.mode {
+      Mode::LogLines => Style::default().fg(self.config.colors.border),
+      _ => Style::default(),
+    };
+    let paragraph = Paragraph::new(log_lines)
+      .block(
+        Block::default()
+          .title(Line::from(logs_title))
+          .borders(Borders::ALL)
+          .border_type(BorderType::Rounded)
+          .border_style(logs_border),
+      )
+      .style(Style::default())
+      .wrap(Wrap { trim: true });
+    self.logs_area = logs_panel;
+
+    // don't scroll past the last page of (wrapped) logs
+    let wrapped_height = paragraph.line_count(logs_panel.width.saturating_sub(2));
+    let max_scroll = wrapped_height.saturating_sub(logs_panel.height as usize).min(u16::MAX as usize) as u16;
+    self.logs_scroll_offset = self.logs_scroll_offset.min(max_scroll);
+
+    // keep the cursor in view
+    if self.mode == Mode::LogLines {
+      let visible_count = visible_logs(&self.logs, self.log_grep.as_ref()).count();
+      self.log_cursor = self.log_cursor.min(visible_count.saturating_sub(1));
+      let (top, bottom) = {
+        let mut heights = self.log_line_heights();
+        let top = heights.by_ref().take(self.log_cursor).sum::<usize>();
+        (top, top + heights.next().unwrap_or(1))
+      };
+      let inner_height = logs_panel.height.saturating_sub(2) as usize;
+      let offset = self.logs_scroll_offset as usize;
+      if top < offset {
+        self.logs_scroll_offset = top.min(u16::MAX as usize) as u16;
+      } else if bottom > offset + inner_height {
+        self.logs_scroll_offset = bottom.saturating_sub(inner_height).min(u16::MAX as usize) as u16;
+      }
+    }
+
+    let paragraph = paragraph.scroll((self.logs_scroll_offset, 0));
+    f.render_widget(paragraph, logs_panel);
+
+    // unit counts go on the right of the status line, messages and prompts on the left
+    let summary = Line::from(self.unit_counts_summary()).right_aligned();
+    let status_line = {
+      let chunks =
+        Layout::new(Direction::Horizontal, [Constraint::Min(0), Constraint::Length(summary.width() as u16 + 1)])
+          .split(status_line);
+      f.render_widget(summary, chunks[1]);
+      chunks[0]
+    };
+
+    if self.mode == Mode::LogGrep {
+      let prompt = if self.log_grep_regex { " Grep logs (regex): " } else { " Grep logs: " };
+      let hint = " (Tab: toggle regex, empty to clear)";
+      let line = Line::from(vec![
+        Span::styled(prompt, Style::default().fg(self.config.colors.border)),
+        Span::raw(self.log_grep_input.value()),
+        Span::styled(hint, Style::default().fg(Color::DarkGray)),
+      ]);
+      f.render_widget(Paragraph::new(line), status_line);
+      f.set_cursor_position((
+        (status_line.x + prompt.len() as u16 + self.log_grep_input.visual_cursor() as u16)
+          .min(status_line.x + status_line.width.saturating_sub(1)),
+        status_line.y,
+      ));
+    } else if self.mode == Mode::LogSearch {
+      let prompt = " Search logs: ";
+      let line = Line::from(vec![
+        Span::styled(prompt, Style::default().fg(self.config.colors.border)),
+        Span::raw(self.log_search_input.value()),
+      ]);
+      f.render_widget(Paragraph::new(line), status_line);
+      f.set_cursor_position((
+        (status_line.x + prompt.len() as u16 + self.log_search_input.visual_cursor() as u16)
+          .min(status_line.x + status_line.width.saturating_sub(1)),
+        status_line.y,
+      ));
+    } else if let Some(alert) = &self.watch_alert {
+      let line = Line::from(vec![
+        Span::styled(format!(" {} ", alert.time.format("%H:%M:%S")), Style::default().fg(Color::DarkGray)),
+        Span::styled(format!("◉ {}", alert.message), Style::default().fg(alert.color).add_modifier(Modifier::BOLD)),
+      ]);
+      f.render_widget(Paragraph::new(line), status_line);
+    } else if let Some(status) = crate::utils::latest_status_message() {
+      // show the most recent warning/error so problems get noticed without opening the logger pane
+      let color =
+        if status.level == tracing::Level::ERROR { self.config.colors.error } else { self.config.colors.warning };
+      let line = Line::from(vec![
+        Span::styled(format!(" {} ", status.time.format("%H:%M:%S")), Style::default().fg(Color::DarkGray)),
+        Span::styled(status.message, Style::default().fg(color)),
+      ]);
+      f.render_widget(Paragraph::new(line), status_line);
+    } else if let Some(error) =
+      systemd::user_units_error().filter(|_| matches!(self.scope, Scope::All) && !self.is_root)
+    {
+      // persistent rather than a one-off status message, the list is missing units until this is fixed.
+      // root usually has no user bus and that's expected, so don't nag about it
+      let line = Line::from(Span::styled(
+        format!(" User services unavailable: {}", error),
+        Style::default().fg(self.config.colors.warning),
+      ));
+      f.render_widget(Paragraph::new(line), status_line);
+    }
+
+    let width = search_panel.width.max(3) - 3; // keep 2 for borders and 1 for cursor
+    let scroll = self.input.visual_scroll(width as usize);
+    let input = Paragraph::new(self.input.value())
+      .style(match self.mode {
+        Mode::Search => Style::default().fg(self.config.colors.border),
+        _ => Style::default(),
+      })
       .scroll((0, scroll as u16))
       .block(Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).title(Line::from(vec![
         Span::raw("─Search "),
@@ -896,53 +3507,402 @@ impl Component for Home {
     }
 
     if self.mode == Mode::Help {
-      let popup = centered_rect_abs(50, 18, f.area());
-
-      fn primary(s: &str) -> Span {
-        Span::styled(s, Style::default().fg(Color::Cyan))
+      let accent = self.config.colors.accent;
+      let primary = |s: &'static str| Span::styled(s, Style::default().fg(accent));
+      fn heading(s: &str) -> Line<'_> {
+        Line::from(Span::styled(s, Style::default().add_modifier(Modifier::UNDERLINED)))
       }
+      // "a / b description"
+      let pair = |a: &'static str, b: &'static str, description: &'static str| {
+        Line::from(vec![primary(a), Span::raw(" / "), primary(b), Span::raw(description)])
+      };
 
       let help_lines = vec![
         Line::from(""),
-        Line::from(Span::styled("Shortcuts", Style::default().add_modifier(Modifier::UNDERLINED))),
+        heading("General"),
         Line::from(""),
-        Line::from(vec![primary("ctrl+C"), Span::raw(" or "), primary("ctrl+Q"), Span::raw(" to quit")]),
+        Line::from(vec![
+          primary("ctrl+C"),
+          Span::raw(" or "),
+          primary("ctrl+Q"),
+          Span::raw(" to quit ("),
+          primary("q"),
+          Span::raw(" in the list)"),
+        ]),
+        Line::from(vec![primary("ctrl+Z"), Span::raw(" suspend to the shell")]),
         Line::from(vec![primary("ctrl+L"), Span::raw(" toggles the logger pane")]),
-        Line::from(vec![primary("PageUp"), Span::raw(" / "), primary("PageDown"), Span::raw(" scroll the logs")]),
-        Line::from(vec![primary("Home"), Span::raw(" / "), primary("End"), Span::raw(" scroll to top/bottom")]),
+        pair("ctrl+↑", "ctrl+↓", " make the logger pane taller/shorter"),
+        Line::from(vec![primary("ctrl+R"), Span::raw(" reload systemd's configuration (daemon-reload)")]),
+        Line::from(vec![
+          primary("ctrl+S"),
+          Span::raw(" / "),
+          primary("ctrl+X"),
+          Span::raw(" / "),
+          primary("ctrl+T"),
+          Span::raw(" start/stop/restart the selected unit, even while searching"),
+        ]),
+        pair("?", "F1", " open this help pane"),
+        Line::from(vec![primary("Esc"), Span::raw(" close a popup, or cancel an action that's running")]),
+        Line::from(""),
+        heading("Services list"),
+        Line::from(""),
+        pair("↑", "↓", " navigate (↑ at the top goes to the search box)"),
         Line::from(vec![primary("Enter"), Span::raw(" or "), primary("Space"), Span::raw(" open the action menu")]),
-        Line::from(vec![primary("?"), Span::raw(" / "), primary("F1"), Span::raw(" open this help pane")]),
+        Line::from(vec![primary("c"), Span::raw(" copy the unit's details to the clipboard")]),
+        Line::from(vec![primary("o"), Span::raw(" open the folder with the unit's file (and its drop-ins)")]),
+        Line::from(vec![
+          primary("a"),
+          Span::raw(" apply changes to the unit's files (daemon-reload, then restart it)"),
+        ]),
+        Line::from(vec![primary("L"), Span::raw(" show the last log line of each unit in the list")]),
+        Line::from(vec![primary("s"), Span::raw(" cycle sorting by name, status, and failed first")]),
+        Line::from(vec![primary("t"), Span::raw(" group units by type")]),
+        Line::from(vec![
+          primary("v"),
+          Span::raw(" mark/unmark a unit, so the action menu starts/stops/restarts all marked units ("),
+          primary("Esc"),
+          Span::raw(" to clear)"),
+        ]),
+        Line::from(vec![primary("T"), Span::raw(" list all timers by when they'll run next")]),
+        Line::from(vec![primary("S"), Span::raw(" switch between global, user, and all units")]),
+        Line::from(vec![primary("F"), Span::raw(" show only failed units ("), primary("Esc"), Span::raw(" to clear)")]),
+        Line::from(vec![primary("h"), Span::raw(" hide/show inactive (dead) units")]),
+        Line::from(vec![primary("H"), Span::raw(" hide/show transient units (made by systemd-run, scopes, etc.)")]),
+        Line::from(vec![
+          primary("W"),
+          Span::raw(" watch the unit: announce (and ring the bell) when its state changes"),
+        ]),
+        Line::from(vec![primary("J"), Span::raw(" open the unit's journal in your pager")]),
+        Line::from(""),
+        heading("Search"),
         Line::from(""),
-        Line::from(Span::styled("Vim Style Shortcuts", Style::default().add_modifier(Modifier::UNDERLINED))),
+        pair("/", "ctrl+F", " filter the list by name"),
+        Line::from(vec![
+          primary("Tab"),
+          Span::raw(" or "),
+          primary("↓"),
+          Span::raw(" back to the list, "),
+          primary("Enter"),
+          Span::raw(" open the action menu"),
+        ]),
+        Line::from(""),
+        heading("Logs"),
+        Line::from(""),
+        pair("PageUp", "PageDown", " scroll the logs"),
+        pair("Home", "End", " scroll to top/bottom"),
+        pair("+", "-", " fetch more/fewer lines of logs"),
+        Line::from(vec![primary("f"), Span::raw(" cycle logs between the tail, this boot, and everything")]),
+        Line::from(vec![primary("w"), Span::raw(" only show logs from a time range")]),
+        pair("p", "P", " raise/lower the minimum log priority"),
+        pair("b", "B", " show logs from an older/newer boot"),
+        Line::from(vec![primary("C"), Span::raw(" toggle coloring logs by level")]),
+        Line::from(vec![
+          primary("O"),
+          Span::raw(" toggle structured logs: aligned, and colored by their real priority"),
+        ]),
+        Line::from(vec![primary("R"), Span::raw(" toggle relative log timestamps (e.g. 3m 2s ago)")]),
+        Line::from(vec![primary("z"), Span::raw(" pause/resume new log lines, so the logs hold still while reading")]),
+        Line::from(vec![
+          primary("l/→"),
+          Span::raw(" move a cursor over the log lines. "),
+          primary("y"),
+          Span::raw(" copies the line, "),
+          primary("Enter"),
+          Span::raw(" shows it in full, "),
+          primary("Esc"),
+          Span::raw(" goes back"),
+        ]),
+        Line::from(vec![
+          primary("|"),
+          Span::raw(" only show log lines containing some text or matching a regex ("),
+          primary("Esc"),
+          Span::raw(" to clear)"),
+        ]),
+        Line::from(vec![
+          primary("ctrl+G"),
+          Span::raw(" search the logs, then "),
+          primary("n"),
+          Span::raw(" / "),
+          primary("N"),
+          Span::raw(" for next/previous match"),
+        ]),
+        Line::from(""),
+        heading("Vim Style Shortcuts"),
         Line::from(""),
         Line::from(vec![primary("j"), Span::raw(" navigate down")]),
         Line::from(vec![primary("k"), Span::raw(" navigate up")]),
-        Line::from(vec![primary("ctrl+U"), Span::raw(" / "), primary("ctrl+D"), Span::raw(" scroll the logs")]),
+        Line::from(vec![
+          primary("ctrl+J"),
+          Span::raw(" / "),
+          primary("ctrl+K"),
+          Span::raw(" or "),
+          primary("}"),
+          Span::raw(" / "),
+          primary("{"),
+          Span::raw(" jump 10 down/up"),
+        ]),
+        pair("gg", "G", " go to the top/bottom"),
+        pair("D", "U", " move half a page down/up"),
+        pair("ctrl+U", "ctrl+D", " scroll the logs"),
       ];
 
       let name = env!("CARGO_PKG_NAME");
       let version = env!("CARGO_PKG_VERSION");
       let title = format!("─Help for {} v{}", name, version);
 
+      // auto-refresh keeps statuses current, but each refresh queries every unit over D-Bus
+      let refresh_footer = match self.config.refresh_interval {
+        0 => " Auto-refresh off, see --refresh-interval ".to_string(),
+        ms => format!(" Refresh: {}s (slower = less load, 0 = off) ", ms as f64 / 1000.0),
+      };
+
       let paragraph = Paragraph::new(help_lines)
-        .block(Block::default().title(title).borders(Borders::ALL).border_type(BorderType::Rounded))
+        .block(
+          Block::default()
+            .title(title)
+            .title_bottom(Line::from(refresh_footer).right_aligned())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded),
+        )
         .style(Style::default())
         .wrap(Wrap { trim: true });
 
+      // fit the popup to the help text, as far as the terminal allows
+      let width = 60.min(f.area().width);
+      let line_count = paragraph.line_count(width.saturating_sub(2)) as u16;
+      let height = line_count.min(f.area().height);
+      let popup = centered_rect_abs(width, height, f.area());
+      self.help_scroll_offset = self.help_scroll_offset.min(line_count - height);
+      let paragraph = paragraph.scroll((self.help_scroll_offset, 0));
+
       f.render_widget(Clear, popup);
       f.render_widget(paragraph, popup);
     }
 
     if self.mode == Mode::Error {
       let popup = centered_rect_abs(50, 12, f.area());
-      let error_lines = self.error_message.split('\n').map(Line::from).collect_vec();
+      let mut error_lines = self.error_message.split('\n').map(Line::from).collect_vec();
+      if self.retry_action.is_some() || self.privileged_retry.is_some() {
+        error_lines.push(Line::from(""));
+      }
+      if self.retry_action.is_some() {
+        error_lines.push(Line::from(vec![
+          Span::raw("Press "),
+          Span::styled("r", Style::default().add_modifier(Modifier::BOLD).fg(self.config.colors.accent)),
+          Span::raw(" to retry"),
+        ]));
+      }
+      if self.privileged_retry.is_some() {
+        error_lines.push(Line::from(vec![
+          Span::raw("Press "),
+          Span::styled("p", Style::default().add_modifier(Modifier::BOLD).fg(self.config.colors.accent)),
+          Span::raw(format!(" to retry with {}", systemd::elevate_command())),
+        ]));
+      }
       let paragraph = Paragraph::new(error_lines)
         .block(
           Block::default()
             .title("─Error")
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Red)),
+            .border_style(Style::default().fg(self.config.colors.error)),
+        )
+        .wrap(Wrap { trim: true });
+
+      f.render_widget(Clear, popup);
+      f.render_widget(paragraph, popup);
+    }
+
+    if self.mode == Mode::LogTimeRange {
+      let popup = centered_rect_abs(60, 6, f.area());
+      let block = Block::default()
+        .title("─Log time range")
+        .title_bottom(Line::from(" Tab to switch, Enter to apply, Esc to cancel ").right_aligned())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(self.config.colors.border));
+      let inner = block.inner(popup);
+
+      let label_width = 8;
+      let field = |label: &str, input: &Input, focused: bool| {
+        let style = if focused { Style::default().fg(self.config.colors.border) } else { Style::default() };
+        Line::from(vec![
+          Span::styled(format!("{:>7} ", label), Style::default().fg(Color::Gray)),
+          Span::styled(input.value().to_string(), style),
+        ])
+      };
+      let lines = vec![
+        field("Since:", &self.log_since_input, !self.editing_log_until),
+        field("Until:", &self.log_until_input, self.editing_log_until),
+        Line::from(""),
+        Line::from(Span::styled(
+          "e.g. \"2024-01-01 10:00\", \"1 hour ago\", \"yesterday\". Blank for no limit",
+          Style::default().fg(Color::DarkGray),
+        )),
+      ];
+
+      f.render_widget(Clear, popup);
+      f.render_widget(Paragraph::new(lines).block(block), popup);
+
+      let (input, row) = if self.editing_log_until { (&self.log_until_input, 1) } else { (&self.log_since_input, 0) };
+      f.set_cursor_position((
+        (inner.x + label_width + input.visual_cursor() as u16).min(inner.x + inner.width.saturating_sub(1)),
+        inner.y + row,
+      ));
+    }
+
+    if self.mode == Mode::Timers {
+      let area = f.area();
+      let height = (self.timers.items.len() as u16 + 3).max(4).min(area.height.saturating_sub(4));
+      let popup = centered_rect_abs(area.width.saturating_sub(10).min(120), height, area);
+      let block = Block::default()
+        .title(format!("─{} Timers", self.scope.name()))
+        .title_bottom(Line::from(" Enter to jump to the unit it activates, Esc to close ").right_aligned())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(self.config.colors.border));
+
+      f.render_widget(Clear, popup);
+      if self.timers.items.is_empty() {
+        let paragraph = Paragraph::new(Span::styled("No timers", Style::default().fg(Color::DarkGray))).block(block);
+        f.render_widget(paragraph, popup);
+      } else {
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+        let chunks = Layout::new(Direction::Vertical, [Constraint::Length(1), Constraint::Min(0)]).split(inner);
+
+        let name_width = self.timers.items.iter().map(|t| t.timer.name.len()).max().unwrap_or(0).clamp(5, 40);
+        let time_width = 16;
+        let row = |timer: &str, next: &str, last: &str, activates: &str| {
+          format!("{:name_width$}  {:time_width$}  {:time_width$}  {}", timer, next, last, activates)
+        };
+        let when = |time: Option<SystemTime>| time.map(format_relative_time).unwrap_or_else(|| "-".into());
+
+        let header = row("Timer", "Next", "Last", "Activates");
+        f.render_widget(Paragraph::new(Span::styled(header, Style::default().add_modifier(Modifier::BOLD))), chunks[0]);
+
+        let items = self
+          .timers
+          .items
+          .iter()
+          .map(|t| {
+            let name = truncate_with_ellipsis(&t.timer.name, name_width);
+            ListItem::new(row(&name, &when(t.next_elapse), &when(t.last_trigger), &t.activates))
+          })
+          .collect_vec();
+        let list = List::new(items).highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+        f.render_stateful_widget(list, chunks[1], &mut self.timers.state);
+      }
+    }
+
+    if self.mode == Mode::Dependencies {
+      let area = f.area();
+      let height = (self.dependencies.items.len() as u16 + 2).max(3).min(area.height.saturating_sub(4));
+      let popup = centered_rect_abs(60, height, area);
+      let title = match &self.dependencies_of {
+        Some(unit) => format!("─Dependencies of {}", unit.name),
+        None => "─Dependencies".to_string(),
+      };
+      let block = Block::default()
+        .title(title)
+        .title_bottom(Line::from(" Enter to jump to a unit, Esc to close ").right_aligned())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(self.config.colors.border));
+
+      f.render_widget(Clear, popup);
+      if self.dependencies.items.is_empty() {
+        let paragraph =
+          Paragraph::new(Span::styled("No dependencies", Style::default().fg(Color::DarkGray))).block(block);
+        f.render_widget(paragraph, popup);
+      } else {
+        let items = self
+          .dependencies
+          .items
+          .iter()
+          .map(|row| match row {
+            DependencyRow::Header(group) => ListItem::new(Span::styled(
+              group.as_str(),
+              Style::default().add_modifier(Modifier::BOLD).fg(self.config.colors.accent),
+            )),
+            DependencyRow::Unit(name) => ListItem::new(format!("  {}", name)),
+          })
+          .collect_vec();
+        let list = List::new(items)
+          .block(block)
+          .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+        f.render_stateful_widget(list, popup, &mut self.dependencies.state);
+      }
+    }
+
+    if self.mode == Mode::UnitFile {
+      let area = f.area();
+      let popup = centered_rect_abs(area.width.saturating_sub(10).min(120), area.height.saturating_sub(4), area);
+      let title = match (&self.unit_file_title, self.selected_unit().and_then(|u| u.file_path.as_ref())) {
+        (Some(title), _) => title.clone(),
+        (None, Some(Ok(path))) => format!("─{}", path),
+        _ => "─Unit file".to_string(),
+      };
+
+      let filter = self.unit_file_filter.value();
+      let highlight = self.config.colors.highlight;
+      let lines = self
+        .unit_file_contents
+        .lines()
+        .filter(|line| filter.is_empty() || !find_matches(line, filter).is_empty())
+        .map(|line| {
+          let trimmed = line.trim_start();
+          if trimmed.starts_with('[') {
+            let style = Style::default().fg(self.config.colors.accent).add_modifier(Modifier::BOLD);
+            Line::from(highlight_matches(line, filter, style, highlight))
+          } else if trimmed.starts_with('#') || trimmed.starts_with(';') {
+            Line::from(highlight_matches(line, filter, Style::default().fg(Color::DarkGray), highlight))
+          } else if let Some((key, value)) = line.split_once('=') {
+            let mut spans = highlight_matches(key, filter, Style::default().fg(self.config.colors.warning), highlight);
+            spans.push(Span::raw("="));
+            spans.extend(highlight_matches(value, filter, Style::default(), highlight));
+            Line::from(spans)
+          } else {
+            Line::from(highlight_matches(line, filter, Style::default(), highlight))
+          }
+        })
+        .collect_vec();
+
+      let bottom = if self.editing_unit_file_filter {
+        Line::from(vec![
+          Span::styled(" Filter: ", Style::default().fg(self.config.colors.accent)),
+          Span::raw(format!("{}█ ", filter)),
+        ])
+      } else if !filter.is_empty() {
+        Line::from(format!(" Filter: {} ({} lines) · / to change, Esc to close ", filter, lines.len()))
+      } else {
+        Line::from(" / to filter, Esc to close ")
+      };
+      let paragraph = Paragraph::new(lines)
+        .block(
+          Block::default()
+            .title(title)
+            .title_bottom(bottom.right_aligned())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.colors.border)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((self.unit_file_scroll_offset, 0));
+
+      f.render_widget(Clear, popup);
+      f.render_widget(paragraph, popup);
+    }
+
+    if self.mode == Mode::Success {
+      let popup = centered_rect_abs(50, 6, f.area());
+      let paragraph = Paragraph::new(self.success_message.as_str())
+        .block(
+          Block::default()
+            .title("─Success")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.colors.border)),
         )
         .wrap(Wrap { trim: true });
 
@@ -950,27 +3910,62 @@ impl Component for Home {
       f.render_widget(paragraph, popup);
     }
 
-    let selected_item = match self.filtered_units.selected() {
-      Some(s) => s,
+    // borrow just `all_units` here, the action menu below writes to other fields
+    let selected_item = match self.filtered_units.selected().and_then(|&i| self.all_units.get_index(i)) {
+      Some((_, s)) => s,
       None => return,
     };
 
-    let min_width = selected_item.name.len() as u16 + 14;
+    // changing system units needs root, unless polkit allows it. So they're flagged in the menu rather than taken away
+    const NEEDS_ROOT: &str = " (needs root)";
+    let flag_needs_root = |item: &MenuItem| match &item.action {
+      Action::RunBatch(_, units) => !self.is_root && units.iter().any(|u| u.scope == UnitScope::Global),
+      action => !self.is_root && selected_item.scope == UnitScope::Global && is_service_action(action),
+    };
+
+    // batch menu items don't include the unit name, and flagged ones are longer. Either can be longer than the name
+    let longest_item = self
+      .menu_items
+      .items
+      .iter()
+      .filter_map(|i| match i.action {
+        _ if flag_needs_root(i) => Some(i.name.len() + NEEDS_ROOT.len()),
+        Action::RunBatch(..) => Some(i.name.len()),
+        _ => None,
+      })
+      .max()
+      .unwrap_or(0);
+    let min_width = selected_item.name.len().max(longest_item) as u16 + 14;
     let desired_width = min_width + 4; // idk, looks alright
     let popup_width = desired_width.min(f.area().width);
 
     if self.mode == Mode::ActionMenu {
       let height = self.menu_items.items.len() as u16 + 2;
       let popup = centered_rect_abs(popup_width, height, f.area());
+      self.action_menu_area = popup;
 
-      let items: Vec<ListItem> = self.menu_items.items.iter().map(|i| ListItem::new(i.name.as_str())).collect();
+      let items: Vec<ListItem> = self
+        .menu_items
+        .items
+        .iter()
+        .map(|i| {
+          let mut line = Line::from(i.name.as_str());
+          if flag_needs_root(i) {
+            line.push_span(Span::styled(NEEDS_ROOT, Style::default().fg(Color::DarkGray)));
+          }
+          ListItem::new(line)
+        })
+        .collect();
       let items = List::new(items)
         .block(
           Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::LightGreen))
-            .title(format!("Actions for {}", self.filtered_units.selected().unwrap().name)),
+            .border_style(Style::default().fg(self.config.colors.border))
+            .title(truncate_with_ellipsis(
+              &format!("Actions for {}", selected_item.name),
+              popup_width.saturating_sub(2) as usize,
+            )),
         )
         .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
 
@@ -978,21 +3973,61 @@ impl Component for Home {
       f.render_stateful_widget(items, popup, &mut self.menu_items.state);
     }
 
+    if self.mode == Mode::Confirm {
+      if let Some(pending) = &self.pending_action {
+        let popup = centered_rect_abs(popup_width.max(40), 6, f.area());
+
+        let lines = vec![
+          match &pending.action {
+            // the name already says which units
+            Action::RunBatch(..) => Line::from(format!("{}?", pending.name)),
+            _ => Line::from(format!("{} {}?", pending.name, selected_item.name)),
+          },
+          Line::from(""),
+          Line::from(vec![
+            Span::styled("y", Style::default().add_modifier(Modifier::BOLD).fg(self.config.colors.accent)),
+            Span::raw(" to confirm, "),
+            Span::styled("n", Style::default().add_modifier(Modifier::BOLD).fg(self.config.colors.accent)),
+            Span::raw(" or "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD).fg(self.config.colors.accent)),
+            Span::raw(" to cancel"),
+          ]),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+          .block(
+            Block::default()
+              .title("─Are you sure?")
+              .border_type(BorderType::Rounded)
+              .borders(Borders::ALL)
+              .border_style(Style::default().fg(self.config.colors.warning)),
+          )
+          .wrap(Wrap { trim: true });
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+      }
+    }
+
     if self.mode == Mode::Processing {
       let height = self.menu_items.items.len() as u16 + 2;
       let popup = centered_rect_abs(popup_width, height, f.area());
 
-      static SPINNER_CHARS: &[char] = &['⣷', '⣯', '⣟', '⡿', '⢿', '⣻', '⣽', '⣾'];
-
       let spinner_char = SPINNER_CHARS[self.spinner_tick as usize % SPINNER_CHARS.len()];
-      // TODO: make this a spinner
-      let paragraph = Paragraph::new(vec![Line::from(format!("{}", spinner_char))])
+      let mut lines = vec![Line::from(format!("{}", spinner_char))];
+      if let Some(status) = &self.processing_status {
+        lines.push(Line::from(Span::styled(status.clone(), Style::default().fg(Color::Gray))));
+      }
+      let paragraph = Paragraph::new(lines)
         .block(
           Block::default()
-            .title("Processing")
+            .title(match self.batch_progress {
+              Some((done, total)) => format!("Processing {}/{}", done, total),
+              None => "Processing".to_string(),
+            })
             .border_type(BorderType::Rounded)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::LightGreen)),
+            .border_style(Style::default().fg(self.config.colors.border)),
         )
         .style(Style::default())
         .wrap(Wrap { trim: true });
@@ -1003,6 +4038,254 @@ impl Component for Home {
   }
 }
 
+/// Actions that run a systemd operation through `spawn_action`, which can be retried if they fail
+fn is_service_action(action: &Action) -> bool {
+  matches!(
+    action,
+    Action::StartService(_)
+      | Action::StopService(_)
+      | Action::RestartService(_)
+      | Action::ReloadService(_)
+      | Action::DaemonReload
+      | Action::ForceStopService(_)
+      | Action::KillService { .. }
+      | Action::EnableService(_)
+      | Action::DisableService(_)
+      | Action::MaskService(_)
+      | Action::UnmaskService(_)
+      | Action::ResetFailed(_)
+      | Action::FreezeService(_)
+      | Action::ThawService(_)
+      | Action::RunBatch(..)
+      | Action::ApplyUnitChanges(_)
+  )
+}
+
+/// The journalctl arguments that select a unit's logs, including any extra matcher configured for it
+fn journal_match_args(unit: &UnitId, matcher: Option<&String>) -> Vec<String> {
+  match matcher {
+    // `-u` can't be OR'd with other matches, so spell out the unit match ourselves
+    Some(matcher) => {
+      let unit_field = match unit.scope {
+        UnitScope::Global => "_SYSTEMD_UNIT",
+        UnitScope::User => "_SYSTEMD_USER_UNIT",
+      };
+      vec![format!("{}={}", unit_field, unit.name), "+".into(), matcher.clone()]
+    },
+    None => vec!["-u".into(), unit.name.clone()],
+  }
+}
+
+// Long values (like descriptions) get wrapped onto this many lines at most in the details panel
+const MAX_DETAIL_LINES: usize = 3;
+
+/// The rows shown in the details panel for a unit, as (property name, value)
+fn unit_details(unit: &UnitWithStatus, colors: &Colors) -> Vec<(&'static str, Line<'static>)> {
+  fn colored(value: String, color: Color) -> Line<'static> {
+    Line::from(vec![Span::styled(value, Style::default().fg(color))])
+  }
+
+  let load_color = match unit.load_state.as_str() {
+    "loaded" => colors.active,
+    "not-found" => colors.not_found,
+    "masked" => colors.info,
+    "error" => colors.error,
+    _ => Color::Reset,
+  };
+
+  let active_color = match unit.activation_state.as_str() {
+    "active" => colors.active,
+    "inactive" => colors.inactive,
+    "failed" => colors.failed,
+    _ => Color::Reset,
+  };
+
+  let scope = match unit.scope {
+    UnitScope::Global => "Global",
+    UnitScope::User => "User",
+  };
+
+  let mut details = vec![
+    ("Description", Line::from(unit.description.clone())),
+    ("Scope", Line::from(scope)),
+    ("Loaded", {
+      let mut loaded = colored(unit.load_state.clone(), load_color);
+      if unit.is_transient() {
+        loaded.push_span(Span::styled(" (transient)", Style::default().fg(colors.warning)));
+      }
+      loaded
+    }),
+    ("Active", {
+      // like systemctl status: how long it's been up, or how long since it stopped
+      let since = match unit.activation_state.as_str() {
+        "active" | "reloading" | "deactivating" => unit.active_enter,
+        "inactive" | "failed" => unit.inactive_enter,
+        _ => None,
+      };
+      let mut active = format!("{} ({})", unit.activation_state, unit.sub_state);
+      if let Some(since) = since {
+        active.push_str(&format!(" since {}", format_relative_time(since)));
+      }
+      colored(active, active_color)
+    }),
+    (
+      "Enabled",
+      match unit.enablement_state.as_deref() {
+        Some(state @ "enabled") => colored(state.into(), colors.active),
+        Some(state @ "disabled") => colored(state.into(), Color::Gray),
+        Some(state @ ("static" | "masked")) => colored(state.into(), colors.warning),
+        Some(state) => Line::from(state.to_string()),
+        None => Line::from(""),
+      },
+    ),
+    (
+      "Unit file",
+      match &unit.file_path {
+        Some(Ok(file_path)) => Line::from(file_path.clone()),
+        Some(Err(e)) => colored(e.clone(), colors.error),
+        None => Line::from(""),
+      },
+    ),
+  ];
+
+  if let Some((template, instance)) = unit.template_instance() {
+    details.insert(1, ("Template", Line::from(template)));
+    details.insert(2, ("Instance", Line::from(instance.to_string())));
+  }
+
+  // one row each, the label only goes on the first
+  for (i, path) in unit.drop_in_paths.iter().flatten().enumerate() {
+    details.push((if i == 0 { "Drop-ins" } else { "" }, Line::from(path.clone())));
+  }
+
+  if unit.name.ends_with(".service") {
+    let main_pid = match (unit.main_pid, &unit.main_process_name) {
+      (Some(pid), Some(name)) if pid != 0 => format!("{} ({})", pid, name),
+      (Some(pid), None) if pid != 0 => pid.to_string(),
+      _ => "-".into(),
+    };
+    details.push(("Main PID", Line::from(main_pid)));
+  }
+  if let Some(socket) = &unit.socket_info {
+    let listening = socket.listen.iter().map(|(kind, address)| format!("{} ({})", address, kind)).join(", ");
+    details.push(("Listening", Line::from(listening)));
+    let accept = if socket.accept { format!("yes ({} connections)", socket.connections) } else { "no".into() };
+    details.push(("Accept", Line::from(accept)));
+  }
+  // only worth mentioning when it's not the usual `running`
+  if let Some(state) = unit.freezer_state.as_deref().filter(|&s| s != "running") {
+    details.push(("Frozen", colored(state.to_string(), colors.accent)));
+  }
+  if let Some(n_restarts) = unit.n_restarts {
+    let color = if n_restarts > 0 { colors.error } else { Color::Reset };
+    details.push(("Restarts", colored(n_restarts.to_string(), color)));
+  }
+  if let Some(memory) = unit.memory_current {
+    details.push(("Memory", Line::from(format_bytes(memory))));
+  }
+  if let Some(cpu) = unit.cpu_usage {
+    // format_duration rounds to seconds, which hides most of what short-lived services use
+    let cpu = if cpu.as_secs() == 0 { format!("{}ms", cpu.as_millis()) } else { format_duration(cpu) };
+    details.push(("CPU time", Line::from(cpu)));
+  }
+
+  if unit.is_timer() {
+    let timer_time = |time: Option<SystemTime>| match time {
+      Some(time) => Line::from(format!(
+        "{} ({})",
+        format_relative_time(time),
+        chrono::DateTime::<chrono::Local>::from(time).format("%Y-%m-%d %H:%M:%S")
+      )),
+      None => colored("n/a".into(), Color::DarkGray),
+    };
+    details.push(("Next run", timer_time(unit.next_elapse)));
+    details.push(("Last run", timer_time(unit.last_trigger)));
+  }
+
+  details
+}
+
+/// A plain text snapshot of the details panel, suitable for sharing
+fn details_as_text(unit: &UnitWithStatus) -> String {
+  let mut text = unit.name.clone();
+  for (label, value) in unit_details(unit, &Colors::default()) {
+    let value: String = value.spans.iter().map(|s| s.content.as_ref()).collect();
+    if label.is_empty() {
+      // continues the row above, e.g. a second drop-in
+      text.push_str(&format!("\n  {}", value));
+    } else {
+      text.push_str(&format!("\n{}: {}", label, value));
+    }
+  }
+  text
+}
+
+/// Wrap a single-style line onto as many as `max_lines` lines of at most `width` columns.
+/// The last line gets an ellipsis if the text doesn't fit.
+fn wrap_line(line: Line<'static>, width: usize, max_lines: usize) -> Vec<Line<'static>> {
+  if width == 0 || line.width() <= width || line.spans.len() != 1 {
+    return vec![line];
+  }
+
+  let style = line.spans[0].style;
+  let mut wrapped = wrap_text(&line.spans[0].content, width);
+  if wrapped.len() > max_lines {
+    let rest = wrapped.split_off(max_lines - 1).join(" ");
+    wrapped.push(truncate_with_ellipsis(&rest, width));
+  }
+  wrapped.into_iter().map(|l| Line::from(Span::styled(l, style))).collect()
+}
+
+/// Word-wrap `text` to lines of at most `width` columns, breaking up words that don't fit on a line by themselves
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+  let mut lines = vec![];
+  let mut current = String::new();
+
+  for word in text.split_whitespace() {
+    let needed = if current.is_empty() { word.width() } else { current.width() + 1 + word.width() };
+    if needed <= width {
+      if !current.is_empty() {
+        current.push(' ');
+      }
+      current.push_str(word);
+      continue;
+    }
+
+    if !current.is_empty() {
+      lines.push(std::mem::take(&mut current));
+    }
+
+    for grapheme in word.graphemes(true) {
+      if !current.is_empty() && current.width() + grapheme.width() > width {
+        lines.push(std::mem::take(&mut current));
+      }
+      current.push_str(grapheme);
+    }
+  }
+
+  if !current.is_empty() || lines.is_empty() {
+    lines.push(current);
+  }
+  lines
+}
+
+/// Truncate `text` to at most `width` columns, ending with an ellipsis if anything was cut off
+fn truncate_with_ellipsis(text: &str, width: usize) -> String {
+  if text.width() <= width {
+    return text.to_string();
+  }
+
+  let mut truncated = String::new();
+  for grapheme in text.graphemes(true) {
+    if truncated.width() + grapheme.width() + 1 > width {
+      break;
+    }
+    truncated.push_str(grapheme);
+  }
+  truncated.push('…');
+  truncated
+}
+
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 fn _centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
   let popup_layout = Layout::new(
@@ -1034,3 +4317,380 @@ fn centered_rect_abs(width: u16, height: u16, r: Rect) -> Rect {
 
   Rect::new(offset_x, offset_y, width, height)
 }
+
+// Byte ranges of (ASCII case-insensitive) occurrences of `query` in `text`
+fn find_matches(text: &str, query: &str) -> Vec<(usize, usize)> {
+  let mut matches = vec![];
+  if query.is_empty() {
+    return matches;
+  }
+  let (text_bytes, query_bytes) = (text.as_bytes(), query.as_bytes());
+  let mut start = 0;
+  while start + query_bytes.len() <= text_bytes.len() {
+    if text_bytes[start..start + query_bytes.len()].eq_ignore_ascii_case(query_bytes) {
+      matches.push((start, start + query_bytes.len()));
+      start += query_bytes.len();
+    } else {
+      start += 1;
+    }
+  }
+  matches
+}
+
+// A service's environment laid out like a unit file, with the contents of each environment file, so it can be shown
+// in the unit file popup
+async fn environment_text(environment: &ServiceEnvironment) -> String {
+  if environment.variables.is_empty() && environment.files.is_empty() {
+    return "# No Environment= or EnvironmentFile= set".into();
+  }
+
+  let mut sections = vec![];
+  if !environment.variables.is_empty() {
+    sections.push(format!("[Environment]\n{}", environment.variables.join("\n")));
+  }
+  for (path, optional) in &environment.files {
+    let contents = match systemd::read_file(path).await {
+      Ok(contents) => contents.trim_end().to_string(),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound && *optional => "# Doesn't exist (optional, ignored)".into(),
+      Err(e) => format!("# Couldn't read it: {}", e),
+    };
+    sections.push(format!("[EnvironmentFile {}]\n{}", path, contents));
+  }
+  sections.join("\n\n")
+}
+
+// The log lines shown in the logs pane, newest first
+fn visible_logs<'a>(logs: &'a [LogLine], grep: Option<&'a LogGrep>) -> impl Iterator<Item = &'a LogLine> {
+  logs.iter().rev().filter(move |l| grep.is_none_or(|grep| grep.is_match(l)))
+}
+
+// Split `text` into spans, giving occurrences of `query` a highlighted background
+fn highlight_matches<'a>(text: &'a str, query: &str, style: Style, highlight: Color) -> Vec<Span<'a>> {
+  let mut spans = vec![];
+  let mut last = 0;
+  for (start, end) in find_matches(text, query) {
+    if start > last {
+      spans.push(Span::styled(&text[last..start], style));
+    }
+    spans.push(Span::styled(&text[start..end], style.bg(highlight).fg(Color::Black)));
+    last = end;
+  }
+  if last < text.len() || spans.is_empty() {
+    spans.push(Span::styled(&text[last..], style));
+  }
+  spans
+}
+
+// Guess a log line's level from markers like `ERROR`, `[warn]`, `level=error`, or a `<3>` syslog priority prefix.
+// Only the first few words are checked, so a message that merely mentions an error later on isn't flagged
+fn log_level_color(message: &str, colors: &Colors) -> Option<Color> {
+  let message = message.trim_start();
+  if let Some(priority) = message.strip_prefix('<').and_then(|m| m.get(..2)).and_then(|m| m.strip_suffix('>')) {
+    if let Some(color) = priority.parse().ok().and_then(|p| priority_color(p, colors)) {
+      return Some(color);
+    }
+  }
+
+  const ERROR_MARKERS: &[&str] =
+    &["error", "err", "fatal", "critical", "crit", "panic", "level=error", "level=fatal", "level=panic"];
+  const WARNING_MARKERS: &[&str] = &["warn", "warning", "level=warn", "level=warning"];
+  // `ERROR`, `[error]`, `Error:` are all the same thing
+  let is_any = |word: &str, markers: &[&str]| markers.iter().any(|m| word.eq_ignore_ascii_case(m));
+  for word in message.split(|c: char| !c.is_ascii_alphanumeric() && c != '=').filter(|w| !w.is_empty()).take(8) {
+    if is_any(word, ERROR_MARKERS) {
+      return Some(colors.error);
+    }
+    if is_any(word, WARNING_MARKERS) {
+      return Some(colors.warning);
+    }
+  }
+  None
+}
+
+// Errors and worse are red, warnings yellow
+fn priority_color(priority: u8, colors: &Colors) -> Option<Color> {
+  match priority {
+    0..=3 => Some(colors.error),
+    4 => Some(colors.warning),
+    _ => None,
+  }
+}
+
+// Turn a `short-iso` journal timestamp like `2024-01-02T03:04:05+0000` into e.g. `   3m 2s ago`.
+// Padded so the messages after it still line up
+fn relative_log_time(timestamp: &str) -> Option<String> {
+  let time = chrono::DateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%z").ok()?;
+  Some(format!("{:>11}", format_relative_time(time.into())))
+}
+
+// Write logs to a timestamped file in the data dir, returning the path
+fn export_logs(unit_name: &str, logs: &[LogLine]) -> anyhow::Result<PathBuf> {
+  let directory = get_data_dir()?;
+  std::fs::create_dir_all(&directory)?;
+  let timestamp = chrono::Local::now().format("%Y-%m-%d-%H-%M-%S");
+  let path = directory.join(format!("logs-{}-{}.txt", unit_name, timestamp));
+  let mut contents = logs.iter().map(|l| &l.text).join("\n");
+  contents.push('\n');
+  std::fs::write(&path, contents).with_context(|| format!("Unable to write {}", path.display()))?;
+  Ok(path)
+}
+
+// The group a unit goes under in the grouped view, and the group's position in the list
+fn unit_group(unit: &UnitWithStatus) -> (u8, &'static str) {
+  match unit.unit_type() {
+    "service" => (0, "Services"),
+    "timer" => (1, "Timers"),
+    "socket" => (2, "Sockets"),
+    "target" => (3, "Targets"),
+    _ => (4, "Other"),
+  }
+}
+
+// Split `text` into spans, making the chars at `indices` (char indices, not bytes) bold and underlined
+fn highlight_chars<'a>(text: &'a str, indices: &[usize], style: Style) -> Vec<Span<'a>> {
+  if indices.is_empty() {
+    return vec![Span::styled(text, style)];
+  }
+
+  let highlighted = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+  let mut spans = vec![];
+  let mut span_start = 0;
+  let mut span_highlighted = false;
+  for (char_index, (byte_index, _)) in text.char_indices().enumerate() {
+    let is_match = indices.contains(&char_index);
+    if is_match != span_highlighted {
+      if byte_index > span_start {
+        spans.push(Span::styled(&text[span_start..byte_index], if span_highlighted { highlighted } else { style }));
+      }
+      span_start = byte_index;
+      span_highlighted = is_match;
+    }
+  }
+  spans.push(Span::styled(&text[span_start..], if span_highlighted { highlighted } else { style }));
+  spans
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Home sends a log request whenever the selection changes, keep the receiver around so that doesn't fail
+  fn test_home() -> (Home, std::sync::mpsc::Receiver<LogRequest>) {
+    let config = Config { watch_bell: false, ..Default::default() };
+    let mut home = Home::new(Scope::All, &[], config);
+    let (journalctl_tx, journalctl_rx) = std::sync::mpsc::channel();
+    home.journalctl_tx = Some(journalctl_tx);
+    (home, journalctl_rx)
+  }
+
+  fn unit(name: &str, scope: UnitScope, activation_state: &str) -> UnitWithStatus {
+    UnitWithStatus {
+      name: name.into(),
+      scope,
+      description: String::new(),
+      file_path: None,
+      load_state: "loaded".into(),
+      activation_state: activation_state.into(),
+      sub_state: if activation_state == "active" { "running".into() } else { "dead".into() },
+      enablement_state: None,
+      next_elapse: None,
+      last_trigger: None,
+      memory_current: None,
+      cpu_usage: None,
+      main_pid: None,
+      main_process_name: None,
+      socket_info: None,
+      n_restarts: None,
+      freezer_state: None,
+      active_enter: None,
+      inactive_enter: None,
+      drop_in_paths: None,
+      environment: None,
+      transient: None,
+    }
+  }
+
+  fn id(name: &str, scope: UnitScope) -> UnitId {
+    UnitId { name: name.into(), scope }
+  }
+
+  fn names(home: &Home) -> Vec<&str> {
+    home.all_units.values().map(|u| u.name.as_str()).collect()
+  }
+
+  #[test]
+  fn update_units_adds_and_updates() {
+    let (mut home, _rx) = test_home();
+    home.update_units(vec![unit("b.service", UnitScope::Global, "active")], &[]);
+    home.update_units(
+      vec![unit("a.service", UnitScope::Global, "active"), unit("b.service", UnitScope::Global, "failed")],
+      &[],
+    );
+
+    assert_eq!(names(&home), ["a.service", "b.service"]);
+    assert_eq!(home.all_units[&id("b.service", UnitScope::Global)].activation_state, "failed");
+    assert_eq!(home.filtered_units.items.len(), 2);
+  }
+
+  #[test]
+  fn partial_batches_dont_remove_units() {
+    let (mut home, _rx) = test_home();
+    home.update_units(
+      vec![unit("a.service", UnitScope::Global, "active"), unit("b.service", UnitScope::Global, "active")],
+      &[],
+    );
+    home.update_units(vec![unit("a.service", UnitScope::Global, "active")], &[]);
+
+    assert_eq!(names(&home), ["a.service", "b.service"]);
+  }
+
+  #[test]
+  fn complete_scopes_remove_missing_units() {
+    let (mut home, _rx) = test_home();
+    home.update_units(
+      vec![
+        unit("a.service", UnitScope::Global, "active"),
+        unit("b.service", UnitScope::Global, "active"),
+        unit("c.service", UnitScope::User, "active"),
+      ],
+      &[],
+    );
+    home.marked_units.insert(id("a.service", UnitScope::Global));
+    home.marked_units.insert(id("b.service", UnitScope::Global));
+
+    // only the system scope is complete, so the missing user unit stays
+    home.update_units(vec![unit("a.service", UnitScope::Global, "active")], &[UnitScope::Global]);
+
+    assert_eq!(names(&home), ["a.service", "c.service"]);
+    assert_eq!(home.marked_units, HashSet::from([id("a.service", UnitScope::Global)]));
+  }
+
+  #[test]
+  fn selection_falls_back_to_the_same_row_when_the_selected_unit_vanishes() {
+    let (mut home, _rx) = test_home();
+    home.update_units(
+      vec![
+        unit("a.service", UnitScope::Global, "active"),
+        unit("b.service", UnitScope::Global, "active"),
+        unit("c.service", UnitScope::Global, "active"),
+      ],
+      &[],
+    );
+    home.select(Some(1), true);
+
+    home.update_units(
+      vec![unit("a.service", UnitScope::Global, "active"), unit("c.service", UnitScope::Global, "active")],
+      &[UnitScope::Global],
+    );
+    assert_eq!(home.selected_service(), Some(id("c.service", UnitScope::Global)));
+
+    // the last row going away selects the new last row
+    home.update_units(vec![unit("a.service", UnitScope::Global, "active")], &[UnitScope::Global]);
+    assert_eq!(home.selected_service(), Some(id("a.service", UnitScope::Global)));
+  }
+
+  #[test]
+  fn watched_units_stay_watched_while_unloaded() {
+    let (mut home, _rx) = test_home();
+    home.update_units(
+      vec![unit("a.service", UnitScope::Global, "active"), unit("b.service", UnitScope::Global, "active")],
+      &[],
+    );
+    home.watched_units.insert(id("b.service", UnitScope::Global));
+
+    home.update_units(vec![unit("a.service", UnitScope::Global, "active")], &[UnitScope::Global]);
+    assert!(home.watch_alert.take().is_some_and(|alert| alert.message == "b.service: active → gone"));
+    assert!(home.watched_units.contains(&id("b.service", UnitScope::Global)));
+
+    home.update_units(
+      vec![unit("a.service", UnitScope::Global, "active"), unit("b.service", UnitScope::Global, "failed")],
+      &[UnitScope::Global],
+    );
+    assert!(home.watch_alert.take().is_some_and(|alert| alert.message == "b.service: gone → failed"));
+  }
+
+  #[test]
+  fn filtering_5000_units_keeps_the_selection() {
+    let (mut home, _rx) = test_home();
+    let units = (0..5000)
+      .map(|i| unit(&format!("unit-{}.service", i), UnitScope::Global, if i % 7 == 0 { "failed" } else { "active" }))
+      .collect_vec();
+    home.update_units(units, &[]);
+    let selected = id("unit-4242.service", UnitScope::Global);
+    let index = home.all_units.get_index_of(&selected).unwrap();
+    let row = home.filtered_units.items.iter().position(|&i| i == index).unwrap();
+    home.select(Some(row), false);
+
+    let start = std::time::Instant::now();
+    home.input = Input::new("42".into());
+    home.sort_mode = SortMode::FailedFirst;
+    home.refilter_units(home.selected_service());
+    let elapsed = start.elapsed();
+
+    assert!(home.filtered_units.items.len() < 5000);
+    assert!(home.filtered_units.items.iter().all(|&i| home.all_units[i].name.contains('4')));
+    assert_eq!(home.selected_service(), Some(selected));
+    // very generous so it holds in debug builds on slow machines, this takes a few ms in release
+    assert!(elapsed < Duration::from_millis(500), "filtering took {:?}", elapsed);
+  }
+
+  #[test]
+  fn log_level_markers_are_case_insensitive() {
+    let colors = Colors::default();
+    for message in ["ERROR something broke", "[error] something broke", "error: something broke", "Fatal: oops"] {
+      assert_eq!(log_level_color(message, &colors), Some(colors.error), "{}", message);
+    }
+    for message in ["WARN disk almost full", "[warn] disk almost full", "Warning: disk almost full", "level=warn msg=x"]
+    {
+      assert_eq!(log_level_color(message, &colors), Some(colors.warning), "{}", message);
+    }
+    assert_eq!(log_level_color("<3>priority prefix", &colors), Some(colors.error));
+    assert_eq!(log_level_color("<4>priority prefix", &colors), Some(colors.warning));
+    assert_eq!(log_level_color("Started the thing", &colors), None);
+    assert_eq!(log_level_color("errors were not found", &colors), None);
+    // only the first few words count
+    assert_eq!(log_level_color("a b c d e f g h i error", &colors), None);
+  }
+
+  #[test]
+  fn log_line_from_json() {
+    let line = LogLine::from_json(
+      r#"{"__REALTIME_TIMESTAMP":"1700000000000000","SYSLOG_IDENTIFIER":"sshd","_PID":"42","PRIORITY":"3","MESSAGE":"oops"}"#,
+    );
+    assert_eq!(line.priority, Some(3));
+    let (time, rest) = line.text.split_once(' ').unwrap();
+    // short-iso's length, which relative times rely on
+    assert_eq!(time.len(), 24);
+    assert_eq!(rest, format!("{:<24} oops", "sshd[42]:"));
+
+    // messages that aren't UTF-8 come as bytes
+    let line = LogLine::from_json(r#"{"__REALTIME_TIMESTAMP":"1700000000000000","_COMM":"foo","MESSAGE":[104,105]}"#);
+    assert_eq!(line.priority, None);
+    assert!(line.text.ends_with(&format!("{:<24} hi", "foo:")));
+
+    // anything else is shown as-is
+    for raw in ["not json", r#"{"MESSAGE":"no timestamp"}"#, r#"{"__REALTIME_TIMESTAMP":"1","MESSAGE":null}"#] {
+      assert_eq!(LogLine::from_json(raw), LogLine::plain(raw));
+    }
+  }
+
+  #[test]
+  fn log_grep_is_match() {
+    let grep = LogGrep::new("Failed", false).unwrap();
+    assert!(grep.is_match("sshd[42]: failed password for root"));
+    assert!(!grep.is_match("sshd[42]: Accepted publickey"));
+
+    // text filters don't treat the pattern as a regex
+    let grep = LogGrep::new("a.c", false).unwrap();
+    assert!(grep.is_match("a.c"));
+    assert!(!grep.is_match("abc"));
+
+    let grep = LogGrep::new(r"code=\d+", true).unwrap();
+    assert!(grep.is_match("exited, code=203"));
+    assert!(!grep.is_match("exited, code=EXEC"));
+    assert_eq!(grep.pattern(), r"code=\d+");
+
+    assert!(LogGrep::new("(unclosed", true).is_err());
+    assert!(LogGrep::new("(unclosed", false).is_ok());
+  }
+}
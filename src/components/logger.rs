@@ -9,11 +9,12 @@ use tokio::sync::mpsc::UnboundedSender;
 use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget, TuiWidgetState};
 
 use super::{Component, Frame};
-use crate::action::Action;
+use crate::{action::Action, config::Colors};
 
 #[derive(Default)]
 pub struct Logger {
   state: TuiWidgetState,
+  pub colors: Colors,
 }
 
 impl Component for Logger {
@@ -25,9 +26,9 @@ impl Component for Logger {
   fn render(&mut self, f: &mut Frame<'_>, rect: Rect) {
     let w = TuiLoggerWidget::default()
       .block(Block::default().title("─systemctl-tui logs").borders(Borders::ALL).border_type(BorderType::Rounded))
-      .style_error(Style::default().fg(Color::Red))
+      .style_error(Style::default().fg(self.colors.error))
       .style_debug(Style::default().fg(Color::Green))
-      .style_warn(Style::default().fg(Color::Yellow))
+      .style_warn(Style::default().fg(self.colors.warning))
       .style_trace(Style::default().fg(Color::Magenta))
       .style_info(Style::default().fg(Color::Cyan))
       .output_separator(':')
@@ -0,0 +1,276 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::{
+  systemd::{Scope, StateFilter},
+  utils::get_config_dir,
+};
+
+/// Settings read from `config.toml` in the config directory. Everything is optional.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+  /// The scope to use when `--scope` isn't passed
+  pub scope: Option<Scope>,
+  /// Start with the logger pane open. After the first run, whatever it was last time is used instead
+  pub show_logger: bool,
+  /// Extra journalctl matches for units whose logs aren't attributed to the unit itself, keyed by unit name.
+  /// For example: `"foo.service" = "SYSLOG_IDENTIFIER=foo"`
+  pub journal_matchers: HashMap<String, String>,
+  /// Only show units in this state at startup. Can be overridden with `--state`
+  pub state: Option<StateFilter>,
+  /// The built-in color theme to start from
+  pub theme: Theme,
+  /// The colors in use: the theme's, with any overrides from the `[colors]` table applied. Filled in by `load`
+  #[serde(skip)]
+  pub colors: Colors,
+  /// Overrides for the theme's colors. Names like `light-green`, hex like `#ff8800`, or 256-color indices
+  #[serde(rename = "colors")]
+  pub color_overrides: ColorOverrides,
+  /// Ask "Are you sure?" before stopping or restarting a unit
+  pub confirm_stop_restart: bool,
+  /// How many lines of logs to fetch for the selected unit. Can be overridden with `--log-lines`
+  pub log_lines: usize,
+  /// Show the UI right away and add units as they load, instead of waiting for all of them. Same as `--incremental-load`
+  pub incremental_load: bool,
+  /// How often to refresh unit statuses, in milliseconds. 0 disables auto-refresh. Same as `--refresh-interval`
+  pub refresh_interval: u64,
+  /// How long to wait for the selection to settle before fetching its logs, in milliseconds. By default it adapts to
+  /// how long journalctl takes. Same as `--log-debounce`
+  pub log_debounce: Option<u64>,
+  /// Keep following the selected unit's logs after fetching them. Turning this off means one less journalctl process
+  /// running in the background. `--no-follow` turns it off
+  pub follow_logs: bool,
+  /// Color log lines that look like errors red and warnings yellow. Toggle with `C`
+  pub colorize_logs: bool,
+  /// Show log timestamps relative to now, e.g. `3m 2s ago`. Toggle with `R`
+  pub relative_log_times: bool,
+  /// Read logs as JSON, to show them aligned and color them by their real priority. Toggle with `O`
+  pub structured_logs: bool,
+  /// Hide units that are inactive (dead) at startup. Toggle with `h`
+  pub hide_inactive: bool,
+  /// Hide transient units (made by `systemd-run`, scopes, etc.) at startup. Toggle with `H`
+  pub hide_transient: bool,
+  /// Ring the terminal bell when a watched unit changes state
+  pub watch_bell: bool,
+  /// Only show log lines containing this. Same as `--log-grep`, change it with `|`
+  pub log_grep: Option<String>,
+  /// Treat `log_grep` as a regex instead of plain text. Same as `--log-grep-regex`
+  pub log_grep_regex: bool,
+  /// Use the mouse for clicking and scrolling. Turn this off to select and copy text with the terminal instead.
+  /// Same as `--no-mouse`
+  pub mouse_capture: bool,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      scope: None,
+      show_logger: false,
+      journal_matchers: HashMap::new(),
+      state: None,
+      theme: Theme::default(),
+      colors: Colors::default(),
+      color_overrides: ColorOverrides::default(),
+      confirm_stop_restart: true,
+      log_lines: 500,
+      incremental_load: false,
+      refresh_interval: 5000,
+      log_debounce: None,
+      follow_logs: true,
+      colorize_logs: true,
+      relative_log_times: false,
+      structured_logs: false,
+      hide_inactive: false,
+      hide_transient: false,
+      watch_bell: true,
+      log_grep: None,
+      log_grep_regex: false,
+      mouse_capture: true,
+    }
+  }
+}
+
+/// Built-in sets of colors, picked with `theme = "..."`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+  #[default]
+  Default,
+  /// Bright colors that are easier to tell apart
+  HighContrast,
+  /// No colors, for terminals with odd palettes
+  Monochrome,
+}
+
+impl Theme {
+  pub fn colors(self) -> Colors {
+    match self {
+      Theme::Default => Colors::default(),
+      Theme::HighContrast => Colors {
+        active: Color::LightGreen,
+        failed: Color::LightRed,
+        inactive: Color::White,
+        not_found: Color::LightYellow,
+        accent: Color::LightCyan,
+        border: Color::LightBlue,
+        warning: Color::LightYellow,
+        error: Color::LightRed,
+        info: Color::LightMagenta,
+        highlight: Color::LightYellow,
+      },
+      Theme::Monochrome => Colors {
+        active: Color::Reset,
+        failed: Color::Reset,
+        inactive: Color::DarkGray,
+        not_found: Color::Reset,
+        accent: Color::Reset,
+        border: Color::Reset,
+        warning: Color::Reset,
+        error: Color::Reset,
+        info: Color::Reset,
+        // shades of gray are fine, matches still need to stand out
+        highlight: Color::Gray,
+      },
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Colors {
+  /// Active units
+  pub active: Color,
+  /// Failed units
+  pub failed: Color,
+  /// Inactive units in the details panel
+  pub inactive: Color,
+  /// Units whose unit file couldn't be found
+  pub not_found: Color,
+  /// Keys in the help and prompts, and group headers
+  pub accent: Color,
+  /// The border of whichever pane or popup has focus
+  pub border: Color,
+  /// Warning log lines, the confirmation popup, and things worth a second look
+  pub warning: Color,
+  /// Error log lines and the error popup
+  pub error: Color,
+  /// Which logs are shown (a previous boot, a time range...) and masked units
+  pub info: Color,
+  /// Background of search matches
+  pub highlight: Color,
+}
+
+impl Default for Colors {
+  fn default() -> Self {
+    Self {
+      active: Color::Green,
+      failed: Color::Red,
+      inactive: Color::Gray,
+      not_found: Color::Yellow,
+      accent: Color::Cyan,
+      border: Color::LightGreen,
+      warning: Color::Yellow,
+      error: Color::Red,
+      info: Color::Magenta,
+      highlight: Color::Yellow,
+    }
+  }
+}
+
+/// The `[colors]` table. Anything set here replaces that color from the theme
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ColorOverrides {
+  pub active: Option<Color>,
+  pub failed: Option<Color>,
+  pub inactive: Option<Color>,
+  pub not_found: Option<Color>,
+  pub accent: Option<Color>,
+  pub border: Option<Color>,
+  pub warning: Option<Color>,
+  pub error: Option<Color>,
+  pub info: Option<Color>,
+  pub highlight: Option<Color>,
+}
+
+impl ColorOverrides {
+  fn apply(&self, colors: &mut Colors) {
+    let fields = [
+      (self.active, &mut colors.active),
+      (self.failed, &mut colors.failed),
+      (self.inactive, &mut colors.inactive),
+      (self.not_found, &mut colors.not_found),
+      (self.accent, &mut colors.accent),
+      (self.border, &mut colors.border),
+      (self.warning, &mut colors.warning),
+      (self.error, &mut colors.error),
+      (self.info, &mut colors.info),
+      (self.highlight, &mut colors.highlight),
+    ];
+    for (value, color) in fields {
+      if let Some(value) = value {
+        *color = value;
+      }
+    }
+  }
+}
+
+impl Config {
+  pub fn path() -> Result<PathBuf> {
+    Ok(get_config_dir()?.join("config.toml"))
+  }
+
+  /// Load the config file, falling back to the defaults if it doesn't exist
+  pub fn load() -> Result<Self> {
+    let path = Self::path()?;
+    let contents = match std::fs::read_to_string(&path) {
+      Ok(contents) => contents,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+      Err(e) => return Err(e).context(format!("Unable to read config file {}", path.display())),
+    };
+    let mut config: Self =
+      toml::from_str(&contents).with_context(|| format!("Invalid config file {}", path.display()))?;
+    config.colors = config.theme.colors();
+    config.color_overrides.apply(&mut config.colors);
+    Ok(config)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn readme_example_parses() {
+    let readme = include_str!("../README.md");
+    let example = readme.split("```toml\n").nth(1).and_then(|rest| rest.split("```").next()).unwrap();
+    let config: Config = toml::from_str(example).unwrap();
+    assert_eq!(config.journal_matchers.get("foo.service").map(String::as_str), Some("SYSLOG_IDENTIFIER=foo"));
+    assert!(config.confirm_stop_restart);
+  }
+
+  #[test]
+  fn color_overrides_apply() {
+    let config: Config = toml::from_str(
+      r##"
+        theme = "high-contrast"
+
+        [colors]
+        failed = "magenta"
+        highlight = "#102030"
+      "##,
+    )
+    .unwrap();
+    let mut colors = config.theme.colors();
+    config.color_overrides.apply(&mut colors);
+
+    assert_eq!(colors.failed, Color::Magenta);
+    assert_eq!(colors.highlight, Color::Rgb(0x10, 0x20, 0x30));
+    // everything else comes from the theme
+    assert_eq!(colors.active, Color::LightGreen);
+    assert_eq!(colors.error, Color::LightRed);
+  }
+}
@@ -0,0 +1,58 @@
+// `--doctor`: check the things systemctl-tui needs and print what's wrong, without starting the TUI
+
+use crate::{
+  systemd::{self, Scope, UnitScope},
+  utils::find_in_path,
+};
+
+struct Check {
+  ok: bool,
+  description: String,
+}
+
+impl Check {
+  fn new(ok: bool, description: impl Into<String>) -> Self {
+    Self { ok, description: description.into() }
+  }
+}
+
+/// Print environment diagnostics. Returns false if any of the checks failed
+pub async fn run(scope: Scope) -> bool {
+  let mut checks = vec![];
+
+  let pid1 = std::fs::read_to_string("/proc/1/comm").map(|comm| comm.trim().to_string());
+  checks.push(match pid1 {
+    Ok(comm) if comm == "systemd" => Check::new(true, "systemd is PID 1"),
+    Ok(comm) => Check::new(false, format!("PID 1 is `{}`, not systemd", comm)),
+    Err(e) => Check::new(false, format!("Couldn't check what PID 1 is: {}", e)),
+  });
+  checks.push(match systemd::booted_with_systemd() {
+    true => Check::new(true, "The system was booted with systemd (/run/systemd/system exists)"),
+    false => Check::new(false, "The system wasn't booted with systemd (/run/systemd/system doesn't exist)"),
+  });
+
+  for (unit_scope, name) in [(UnitScope::Global, "system"), (UnitScope::User, "session")] {
+    checks.push(match systemd::get_systemd_version(unit_scope).await {
+      Ok(version) => Check::new(true, format!("The {} bus is reachable (systemd {})", name, version)),
+      Err(e) => Check::new(false, format!("The {} bus isn't reachable: {:#}", name, e)),
+    });
+  }
+
+  for program in ["systemctl", "journalctl"] {
+    checks.push(match find_in_path(program) {
+      Some(path) => Check::new(true, format!("`{}` is at {}", program, path.display())),
+      None => Check::new(false, format!("`{}` isn't on the PATH", program)),
+    });
+  }
+
+  for check in &checks {
+    println!("{} {}", if check.ok { "✓" } else { "✗" }, check.description);
+  }
+
+  // informational, not pass/fail
+  println!();
+  println!("WSL: {}", if is_wsl::is_wsl() { "yes" } else { "no" });
+  println!("Scope: {}", scope.name());
+
+  checks.iter().all(|check| check.ok)
+}
@@ -1,6 +1,6 @@
 use std::{sync::Arc, time::Duration};
 
-use crossterm::event::{Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent};
+use crossterm::event::{Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind};
 use futures::{FutureExt, StreamExt};
 use tokio::{
   sync::{mpsc, Mutex},
@@ -30,19 +30,29 @@ pub struct EventHandler {
   cancellation_token: CancellationToken,
 }
 
-const SERVICE_REFRESH_INTERVAL_MS: u64 = 5000;
-
 impl EventHandler {
-  pub fn new(home: Arc<Mutex<Home>>, action_tx: mpsc::UnboundedSender<Action>) -> Self {
+  /// `refresh_interval` is how often to send a `RefreshTick`. If it's zero, they're never sent
+  pub fn new(home: Arc<Mutex<Home>>, action_tx: mpsc::UnboundedSender<Action>, refresh_interval: Duration) -> Self {
     let (event_tx, mut event_rx) = mpsc::unbounded_channel();
     let cancellation_token = CancellationToken::new();
     let _cancellation_token = cancellation_token.clone();
     let task = tokio::spawn(async move {
       let mut reader = crossterm::event::EventStream::new();
-      let mut refresh_services_interval = tokio::time::interval(Duration::from_millis(SERVICE_REFRESH_INTERVAL_MS));
-      refresh_services_interval.tick().await;
+      let mut refresh_services_interval =
+        (!refresh_interval.is_zero()).then(|| tokio::time::interval(refresh_interval));
+      // the first tick completes immediately, skip it
+      if let Some(interval) = &mut refresh_services_interval {
+        interval.tick().await;
+      }
       loop {
-        let refresh_delay = refresh_services_interval.tick();
+        let refresh_delay = async {
+          match &mut refresh_services_interval {
+            Some(interval) => {
+              interval.tick().await;
+            },
+            None => std::future::pending().await,
+          }
+        };
         let crossterm_event = reader.next().fuse();
         tokio::select! {
           _ = _cancellation_token.cancelled() => {
@@ -52,10 +62,12 @@ impl EventHandler {
             match maybe_event {
               Some(Ok(evt)) => {
                 match evt {
-                  CrosstermEvent::Key(key) => {
-                    if key.kind == KeyEventKind::Press {
-                      event_tx.send(Event::Key(key)).unwrap();
-                    }
+                  CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => {
+                    event_tx.send(Event::Key(key)).unwrap();
+                  },
+                  // we don't do hover effects, no point in waking up for every mouse movement
+                  CrosstermEvent::Mouse(mouse) if mouse.kind != MouseEventKind::Moved => {
+                    event_tx.send(Event::Mouse(mouse)).unwrap();
                   },
                   // interestingly, we never get these if running in dev mode with watchexec
                   CrosstermEvent::Resize(x, y) => {
@@ -4,8 +4,14 @@ pub mod action;
 
 pub mod components;
 
+pub mod config;
+
+pub mod doctor;
+
 pub mod event;
 
+pub mod state;
+
 pub mod terminal;
 
 pub mod utils;
@@ -1,8 +1,13 @@
+use std::io::Write;
+
 use anyhow::Result;
 use clap::{Parser, ValueEnum};
 use systemctl_tui::{
   app::App,
-  systemd,
+  components::home::{BatchOperation, LogGrep, StartupUnit},
+  config::Config,
+  doctor, systemd,
+  terminal::{set_output_stream, OutputStream},
   utils::{initialize_logging, initialize_panic_handler, version},
 };
 
@@ -10,15 +15,72 @@ use systemctl_tui::{
 #[derive(Parser, Debug)]
 #[command(version = version(), about = "A simple TUI for systemd services")]
 struct Args {
-  /// The scope of the services to display. Defaults to "all" normally and "global" on WSL
+  /// The scope of the services to display. Defaults to the config file's `scope`, or "all" normally and "global" on WSL
   #[clap(short, long)]
   scope: Option<Scope>,
+  /// Shorthand for `--scope user`
+  #[clap(long, conflicts_with_all = ["scope", "system"])]
+  user: bool,
+  /// Shorthand for `--scope global`
+  #[clap(long, conflicts_with = "scope")]
+  system: bool,
+  /// Manage systemd on another machine over ssh (`user@server`). Needs systemd-stdio-bridge there, like
+  /// `systemctl --host`
+  #[clap(short = 'H', long, conflicts_with = "doctor")]
+  host: Option<String>,
   /// Enable performance tracing (in Chromium Event JSON format)
   #[clap(short, long)]
   trace: bool,
-  /// Limit view to only these unit files
-  #[clap(short, long, default_value="*.service", num_args=1..)]
+  /// Only show units in this state at startup (e.g. `--state failed`)
+  #[clap(long, visible_alias = "filter-state")]
+  state: Option<systemd::StateFilter>,
+  /// How many lines of logs to fetch for the selected unit (default 500)
+  #[clap(long)]
+  log_lines: Option<usize>,
+  /// Only show log lines containing this text (case-insensitive). Change or clear it in the TUI with `|`
+  #[clap(long, value_name = "PATTERN")]
+  log_grep: Option<String>,
+  /// Treat `--log-grep` as a regex
+  #[clap(long, requires = "log_grep")]
+  log_grep_regex: bool,
+  /// Show the UI right away and add units as they load. Useful on systems with thousands of units
+  #[clap(long)]
+  incremental_load: bool,
+  /// How often to refresh unit statuses, in milliseconds (default 5000). 0 disables auto-refresh
+  #[clap(long)]
+  refresh_interval: Option<u64>,
+  /// How long to wait for the selection to settle before fetching logs, in milliseconds. Adapts to how long journalctl
+  /// takes by default
+  #[clap(long, value_name = "MS")]
+  log_debounce: Option<u64>,
+  /// Only fetch the last lines of logs, don't keep a `journalctl --follow` process running for new ones
+  #[clap(long)]
+  no_follow: bool,
+  /// Don't capture the mouse, so the terminal can select and copy text. Clicking and scrolling in the TUI won't work
+  #[clap(long)]
+  no_mouse: bool,
+  /// Check the environment (systemd, D-Bus, journalctl...) and print what's wrong instead of starting the TUI
+  #[clap(long, visible_alias = "check")]
+  doctor: bool,
+  /// Select this unit once units are loaded (`nginx` is short for `nginx.service`)
+  #[clap(long, value_name = "UNIT")]
+  select: Option<String>,
+  /// Ask whether to start/stop/restart the `--select`ed unit right away. Handy as a confirmation prompt in scripts
+  #[clap(long, value_enum, requires = "select")]
+  action: Option<BatchOperation>,
+  /// Print the units as JSON and exit instead of starting the TUI
+  #[clap(long)]
+  json: bool,
+  /// Which stream to draw the TUI on
+  #[clap(long, value_enum, default_value_t = OutputStream::Stderr)]
+  output_stream: OutputStream,
+  /// Limit view to only these unit files. Same as passing patterns positionally
+  #[clap(short, long, visible_alias = "unit", value_name = "PATTERN", num_args=1..)]
   limit_units: Vec<String>,
+  /// Only load units matching these systemd glob patterns (e.g. `nginx* redis*`). Defaults to `*.service`.
+  /// Unlike the search box, these are passed straight to systemd and aren't fuzzy
+  #[clap(value_name = "PATTERN")]
+  units: Vec<String>,
 }
 
 #[derive(Parser, Debug, ValueEnum, Clone)]
@@ -38,24 +100,112 @@ async fn main() -> Result<()> {
   let args = Args::parse();
   let _guard = initialize_logging(args.trace)?;
   initialize_panic_handler();
+  set_output_stream(args.output_stream);
+
+  let mut limit_units = args.limit_units;
+  limit_units.extend(args.units);
+  if limit_units.is_empty() {
+    limit_units.push("*.service".to_string());
+  }
+
+  let mut config = Config::load()?;
+  // CLI args take precedence over the config file
+  if args.state.is_some() {
+    config.state = args.state;
+  }
+  if args.incremental_load {
+    config.incremental_load = true;
+  }
+  if let Some(log_lines) = args.log_lines {
+    config.log_lines = log_lines;
+  }
+  if let Some(refresh_interval) = args.refresh_interval {
+    config.refresh_interval = refresh_interval;
+  }
+  if args.log_debounce.is_some() {
+    config.log_debounce = args.log_debounce;
+  }
+  if args.no_follow {
+    config.follow_logs = false;
+  }
+  if args.no_mouse {
+    config.mouse_capture = false;
+  }
+  if args.log_grep.is_some() {
+    config.log_grep = args.log_grep;
+    config.log_grep_regex = args.log_grep_regex;
+  }
+  if let Some(pattern) = &config.log_grep {
+    if let Err(e) = LogGrep::new(pattern, config.log_grep_regex) {
+      eprintln!("Invalid log grep regex: {}", e);
+      std::process::exit(1);
+    }
+  }
 
   // There's probably a nicer way to do this than defining the scope enum twice, but this is fine for now
-  let scope = match args.scope {
+  let scope_arg = match (args.user, args.system) {
+    (true, _) => Some(Scope::User),
+    (_, true) => Some(Scope::Global),
+    _ => args.scope,
+  };
+  let scope = match scope_arg {
     Some(Scope::Global) => systemd::Scope::Global,
     Some(Scope::User) => systemd::Scope::User,
     Some(Scope::All) => systemd::Scope::All,
-    // So, WSL doesn't *really* support user services yet: https://github.com/microsoft/WSL/issues/8842
-    // Revisit this if that changes
-    None => {
-      if is_wsl::is_wsl() {
-        systemd::Scope::Global
-      } else {
-        systemd::Scope::All
-      }
+    None => match config.scope {
+      Some(scope) => scope,
+      // So, WSL doesn't *really* support user services yet: https://github.com/microsoft/WSL/issues/8842
+      // Revisit this if that changes
+      None if is_wsl::is_wsl() => systemd::Scope::Global,
+      None => systemd::Scope::All,
     },
   };
 
-  let mut app = App::new(scope, args.limit_units)?;
+  if args.doctor {
+    let ok = doctor::run(scope).await;
+    std::process::exit(if ok { 0 } else { 1 });
+  }
+
+  if let Some(host) = &args.host {
+    if let Err(e) = systemd::connect_to_host(host, scope).await {
+      eprintln!("{:#}", e);
+      std::process::exit(1);
+    }
+  }
+
+  // Without systemd the D-Bus calls fail in confusing ways, or the TUI just comes up empty. Some containers talk to a
+  // host's systemd without running it themselves though, so only give up if it can't be reached either
+  if args.host.is_none()
+    && !systemd::booted_with_systemd()
+    && systemd::get_systemd_version(systemd::UnitScope::Global).await.is_err()
+  {
+    let init = std::fs::read_to_string("/proc/1/comm").map(|comm| format!(" (PID 1 is `{}`)", comm.trim()));
+    eprintln!(
+      "systemctl-tui only works on systems that use systemd, and this one doesn't seem to{}.\n\
+       Run `systemctl-tui --doctor` for details.",
+      init.unwrap_or_default()
+    );
+    std::process::exit(1);
+  }
+
+  if args.json {
+    let units = match systemd::get_all_services(scope, &limit_units).await {
+      Ok(units) => units,
+      Err(e) => {
+        // just the message, a backtrace doesn't help anyone fix their D-Bus setup
+        eprintln!("Unable to get services: {:#}", e);
+        std::process::exit(1);
+      },
+    };
+    let summaries: Vec<systemd::UnitSummary> = units.iter().map(Into::into).collect();
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &summaries)?;
+    writeln!(stdout)?;
+    return Ok(());
+  }
+
+  let startup_unit = args.select.map(|name| StartupUnit { name, action: args.action });
+  let mut app = App::new(scope, limit_units, config, startup_unit)?;
   app.run().await?;
 
   Ok(())
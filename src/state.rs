@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{systemd::UnitId, utils::get_data_dir};
+
+/// Things remembered between runs, stored as `state.toml` in the data directory
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+  /// The unit that was selected when we last quit
+  pub last_selected: Option<UnitId>,
+  /// Whether the logger pane was open
+  pub show_logger: Option<bool>,
+  /// How much of the screen's height the logger pane took up, in percent
+  pub logger_percent: Option<u16>,
+}
+
+impl State {
+  pub fn path() -> Result<PathBuf> {
+    Ok(get_data_dir()?.join("state.toml"))
+  }
+
+  /// Load the saved state. This is a nicety, so a missing or broken file just means starting fresh
+  pub fn load() -> Self {
+    let contents = match Self::path().and_then(|path| Ok(std::fs::read_to_string(path)?)) {
+      Ok(contents) => contents,
+      Err(_) => return Self::default(),
+    };
+    toml::from_str(&contents).unwrap_or_else(|e| {
+      warn!("Ignoring invalid state file: {}", e);
+      Self::default()
+    })
+  }
+
+  pub fn save(&self) -> Result<()> {
+    let path = Self::path()?;
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, toml::to_string(self)?).with_context(|| format!("Unable to write {}", path.display()))
+  }
+}
@@ -1,13 +1,21 @@
 // File initially taken from https://github.com/servicer-labs/servicer/blob/master/src/utils/systemd.rs, since modified
 
 use core::str;
-use std::process::Command;
+use std::{
+  collections::HashSet,
+  os::fd::OwnedFd,
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+  sync::OnceLock,
+  time::{Duration, SystemTime},
+};
 
 use anyhow::{bail, Context, Result};
-use log::error;
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt};
 use tokio_util::sync::CancellationToken;
-use tracing::info;
-use zbus::{proxy, zvariant, Connection};
+use tracing::{info, warn};
+use zbus::{proxy, zvariant, Connection, DBusError};
 
 #[derive(Debug, Clone)]
 pub struct UnitWithStatus {
@@ -27,6 +35,36 @@ pub struct UnitWithStatus {
   /// The other state all units have is called the "enablement state". It describes how the unit might be automatically started in the future. A unit is enabled if it has been added to the requirements list of any other unit though symlinks in the filesystem. The set of symlinks to be created when enabling a unit is described by the unit's [Install] section. A unit is disabled if no symlinks are present. Again there's a variety of other values other than these two (e.g. not all units even have [Install] sections).
   /// Only populated when needed b/c this is much slower to get
   pub enablement_state: Option<String>,
+
+  /// When a timer unit will next fire. Only populated for timers, on demand
+  pub next_elapse: Option<SystemTime>,
+  /// When a timer unit last fired. Only populated for timers, on demand
+  pub last_trigger: Option<SystemTime>,
+  /// Memory used by an active service, in bytes. Populated on demand
+  pub memory_current: Option<u64>,
+  /// CPU time used by an active service. Populated on demand
+  pub cpu_usage: Option<Duration>,
+  /// The main process of an active service (0 if there isn't one). Populated on demand
+  pub main_pid: Option<u32>,
+  /// The name of the main process, from `/proc/<pid>/comm`
+  pub main_process_name: Option<String>,
+  /// What a socket unit listens on. Only populated for sockets, on demand
+  pub socket_info: Option<SocketInfo>,
+  /// How many times systemd has automatically restarted a service. Only populated for services, on demand
+  pub n_restarts: Option<u32>,
+  /// `running`, `frozen`, `freezing`, or `thawing`. Populated on demand
+  pub freezer_state: Option<String>,
+  /// When the unit last became active. Populated on demand
+  pub active_enter: Option<SystemTime>,
+  /// When the unit last became inactive (or failed). Populated on demand
+  pub inactive_enter: Option<SystemTime>,
+  /// Override files (e.g. `foo.service.d/override.conf`) applied on top of the unit file. Populated on demand
+  pub drop_in_paths: Option<Vec<String>>,
+  /// `Environment=` and `EnvironmentFile=` of a service. Only populated for services, on demand
+  pub environment: Option<ServiceEnvironment>,
+  /// Created at runtime (by `systemd-run`, for scopes, etc.) rather than from a unit file on disk.
+  /// Guessed from systemd's transient unit directory when units are listed, then asked over D-Bus on demand
+  pub transient: Option<bool>,
   // We don't use any of these right now, might as well skip'em so there's less data to clone
   // pub followed: String, // A unit that is being followed in its state by this unit, if there is any, otherwise the empty string.
   // pub path: String,     // The unit object path
@@ -35,14 +73,67 @@ pub struct UnitWithStatus {
   // pub job_path: String, // The job object path
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum UnitScope {
   Global,
   User,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketInfo {
+  /// (type, address) pairs, e.g. `("Stream", "[::]:22")`
+  pub listen: Vec<(String, String)>,
+  /// Whether a service instance is spawned for each connection (`Accept=yes`)
+  pub accept: bool,
+  pub connections: u32,
+}
+
+/// Where a service's environment variables come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceEnvironment {
+  /// `KEY=value` pairs from `Environment=`
+  pub variables: Vec<String>,
+  /// Files from `EnvironmentFile=`, with whether it's fine for them to be missing (the `-` prefix)
+  pub files: Vec<(String, bool)>,
+}
+
+/// A row in the timers view
+#[derive(Debug, Clone)]
+pub struct TimerInfo {
+  pub timer: UnitId,
+  pub next_elapse: Option<SystemTime>,
+  pub last_trigger: Option<SystemTime>,
+  /// The unit the timer starts, usually a service with the same name
+  pub activates: String,
+}
+
+/// The parts of a unit that are printed by `--json`
+#[derive(Debug, serde::Serialize)]
+pub struct UnitSummary<'a> {
+  pub name: &'a str,
+  pub scope: UnitScope,
+  pub description: &'a str,
+  pub load_state: &'a str,
+  pub active_state: &'a str,
+  pub sub_state: &'a str,
+}
+
+impl<'a> From<&'a UnitWithStatus> for UnitSummary<'a> {
+  fn from(unit: &'a UnitWithStatus) -> Self {
+    Self {
+      name: &unit.name,
+      scope: unit.scope,
+      description: &unit.description,
+      load_state: &unit.load_state,
+      active_state: &unit.activation_state,
+      sub_state: &unit.sub_state,
+    }
+  }
+}
+
 /// Just enough info to fully identify a unit
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct UnitId {
   pub name: String,
   pub scope: UnitScope,
@@ -57,14 +148,36 @@ impl UnitWithStatus {
     self.activation_state == "failed"
   }
 
+  /// Inactive and not doing anything, which is most units on most systems
+  pub fn is_dead(&self) -> bool {
+    self.activation_state == "inactive" && self.sub_state == "dead"
+  }
+
+  pub fn is_transient(&self) -> bool {
+    self.transient == Some(true)
+  }
+
   pub fn is_not_found(&self) -> bool {
     self.load_state == "not-found"
   }
 
+  pub fn is_masked(&self) -> bool {
+    self.load_state == "masked"
+  }
+
   pub fn is_enabled(&self) -> bool {
     self.load_state == "loaded" && self.activation_state == "active"
   }
 
+  pub fn is_timer(&self) -> bool {
+    self.name.ends_with(".timer")
+  }
+
+  /// The unit type, e.g. `service` or `timer`
+  pub fn unit_type(&self) -> &str {
+    self.name.rsplit_once('.').map_or("", |(_, suffix)| suffix)
+  }
+
   pub fn short_name(&self) -> &str {
     if self.name.ends_with(".service") {
       &self.name[..self.name.len() - 8]
@@ -73,6 +186,18 @@ impl UnitWithStatus {
     }
   }
 
+  /// For an instance of a template unit, the template and the instance name,
+  /// e.g. `getty@tty1.service` -> (`getty@.service`, `tty1`)
+  pub fn template_instance(&self) -> Option<(String, &str)> {
+    let (prefix, rest) = self.name.split_once('@')?;
+    let (instance, unit_type) = rest.rsplit_once('.')?;
+    if instance.is_empty() {
+      // the template itself
+      return None;
+    }
+    Some((format!("{}@.{}", prefix, unit_type), instance))
+  }
+
   // TODO: should we have a non-allocating version of this?
   pub fn id(&self) -> UnitId {
     UnitId { name: self.name.clone(), scope: self.scope }
@@ -80,10 +205,26 @@ impl UnitWithStatus {
 
   // useful for updating without wiping out the file path
   pub fn update(&mut self, other: UnitWithStatus) {
+    // the state changed since we looked up when it last changed, that's stale now
+    if self.activation_state != other.activation_state {
+      self.active_enter = None;
+      self.inactive_enter = None;
+    }
     self.description = other.description;
     self.load_state = other.load_state;
     self.activation_state = other.activation_state;
     self.sub_state = other.sub_state;
+    if other.transient.is_some() {
+      self.transient = other.transient;
+    }
+    // resource usage (and the freezer state) is only fetched for active units, don't keep showing it after they stop
+    if !self.is_active() {
+      self.memory_current = None;
+      self.cpu_usage = None;
+      self.main_pid = None;
+      self.main_process_name = None;
+      self.freezer_state = None;
+    }
   }
 }
 
@@ -100,14 +241,56 @@ fn to_unit_status(raw_unit: RawUnit, scope: UnitScope) -> UnitWithStatus {
     description,
     file_path: None,
     enablement_state: None,
+    next_elapse: None,
+    last_trigger: None,
+    memory_current: None,
+    cpu_usage: None,
+    main_pid: None,
+    main_process_name: None,
+    socket_info: None,
+    n_restarts: None,
+    freezer_state: None,
+    active_enter: None,
+    inactive_enter: None,
+    drop_in_paths: None,
+    environment: None,
+    transient: None,
     load_state,
     activation_state: active_state,
     sub_state,
   }
 }
 
+/// Restricts the services list to units in a particular state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateFilter {
+  Active,
+  Failed,
+  Inactive,
+}
+
+impl StateFilter {
+  pub fn matches(&self, unit: &UnitWithStatus) -> bool {
+    match self {
+      StateFilter::Active => unit.is_active(),
+      StateFilter::Failed => unit.is_failed(),
+      StateFilter::Inactive => unit.activation_state == "inactive",
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      StateFilter::Active => "active",
+      StateFilter::Failed => "failed",
+      StateFilter::Inactive => "inactive",
+    }
+  }
+}
+
 // Different from UnitScope in that this is not for 1 specific unit (i.e. it can include multiple scopes)
-#[derive(Clone, Copy, Default, Debug)]
+#[derive(Clone, Copy, Default, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Scope {
   Global,
   User,
@@ -115,14 +298,62 @@ pub enum Scope {
   All,
 }
 
+impl Scope {
+  pub fn next(self) -> Self {
+    match self {
+      Scope::Global => Scope::User,
+      Scope::User => Scope::All,
+      Scope::All => Scope::Global,
+    }
+  }
+
+  pub fn includes(&self, unit_scope: UnitScope) -> bool {
+    match self {
+      Scope::Global => unit_scope == UnitScope::Global,
+      Scope::User => unit_scope == UnitScope::User,
+      Scope::All => true,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Scope::Global => "Global",
+      Scope::User => "User",
+      Scope::All => "All",
+    }
+  }
+}
+
+// Why user units couldn't be loaded last time we tried with scope=all, if they couldn't
+static USER_UNITS_ERROR: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// If user units couldn't be loaded (e.g. there's no session bus in containers or cron), why not.
+/// System units are still shown in that case
+pub fn user_units_error() -> Option<String> {
+  USER_UNITS_ERROR.lock().unwrap().clone()
+}
+
+// Record whether loading user units worked. Only logged when it starts failing, this runs on every refresh
+fn set_user_units_error(error: Option<&anyhow::Error>) {
+  let mut current = USER_UNITS_ERROR.lock().unwrap();
+  if let Some(e) = error {
+    if current.is_none() {
+      if nix::unistd::geteuid().is_root() {
+        info!("Failed to get user units, ignoring because we're running as root: {:#}", e);
+      } else {
+        warn!("Failed to get user units, showing system units only: {:#}", e);
+      }
+    }
+  }
+  *current = error.map(|e| format!("{:#}", e));
+}
+
 // this takes like 5-10 ms on 13th gen Intel i7 (scope=all)
 pub async fn get_all_services(scope: Scope, services: &[String]) -> Result<Vec<UnitWithStatus>> {
   let start = std::time::Instant::now();
 
   let mut units = vec![];
 
-  let is_root = nix::unistd::geteuid().is_root();
-
   match scope {
     Scope::Global => {
       let system_units = get_services(UnitScope::Global, services).await?;
@@ -137,33 +368,101 @@ pub async fn get_all_services(scope: Scope, services: &[String]) -> Result<Vec<U
         tokio::join!(get_services(UnitScope::Global, services), get_services(UnitScope::User, services));
       units.extend(system_units?);
 
-      // Should always be able to get user units, but it may fail when running as root
-      if let Ok(user_units) = user_units {
-        units.extend(user_units);
-      } else if is_root {
-        error!("Failed to get user units, ignoring because we're running as root")
-      } else {
-        user_units?;
+      // There may not be a user bus to talk to (running as root, in a container, from cron...). Still show system units
+      match user_units {
+        Ok(user_units) => {
+          set_user_units_error(None);
+          units.extend(user_units);
+        },
+        Err(e) => set_user_units_error(Some(&e)),
       }
     },
   }
 
   // sort by name case-insensitive
-  units.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+  units.sort_by_key(|u| u.name.to_lowercase());
 
   info!("Loaded systemd services in {:?}", start.elapsed());
 
   Ok(units)
 }
 
+/// Like `get_all_services`, but hands over each scope's units as soon as they're loaded instead of waiting for all of them
+pub async fn get_all_services_incrementally(
+  scope: Scope,
+  services: &[String],
+  mut on_batch: impl FnMut(Vec<UnitWithStatus>),
+) -> Result<()> {
+  let scopes = match scope {
+    Scope::Global => vec![UnitScope::Global],
+    Scope::User => vec![UnitScope::User],
+    Scope::All => vec![UnitScope::Global, UnitScope::User],
+  };
+  let mut pending: FuturesUnordered<_> = scopes
+    .into_iter()
+    .map(|unit_scope| async move { (unit_scope, get_services(unit_scope, services).await) })
+    .collect();
+
+  while let Some((unit_scope, result)) = pending.next().await {
+    match result {
+      Ok(mut units) => {
+        if unit_scope == UnitScope::User && matches!(scope, Scope::All) {
+          set_user_units_error(None);
+        }
+        units.sort_by_key(|u| u.name.to_lowercase());
+        on_batch(units);
+      },
+      // same as get_all_services, there may not be a user bus. Carry on with system units
+      Err(e) if unit_scope == UnitScope::User && matches!(scope, Scope::All) => set_user_units_error(Some(&e)),
+      Err(e) => return Err(e),
+    }
+  }
+
+  Ok(())
+}
+
 async fn get_services(scope: UnitScope, services: &[String]) -> Result<Vec<UnitWithStatus>, anyhow::Error> {
   let connection = get_connection(scope).await?;
   let manager_proxy = ManagerProxy::new(&connection).await?;
   let units = manager_proxy.list_units_by_patterns(vec![], services.to_vec()).await?;
-  let units: Vec<_> = units.into_iter().map(|u| to_unit_status(u, scope)).collect();
+  let transient = transient_unit_names(scope);
+  let units: Vec<_> = units
+    .into_iter()
+    .map(|u| {
+      let mut unit = to_unit_status(u, scope);
+      unit.transient = transient.as_ref().map(|names| names.contains(&unit.name));
+      unit
+    })
+    .collect();
   Ok(units)
 }
 
+// systemd writes out a unit file for every transient unit it creates. Listing them is much cheaper than asking
+// about each unit over D-Bus. None if we don't know where the directory is or can't read it
+fn transient_unit_names(scope: UnitScope) -> Option<HashSet<String>> {
+  // the directory is on the other machine
+  if remote_host().is_some() {
+    return None;
+  }
+  let dir = match scope {
+    UnitScope::Global => PathBuf::from("/run/systemd/transient"),
+    UnitScope::User => PathBuf::from(std::env::var_os("XDG_RUNTIME_DIR")?).join("systemd/transient"),
+  };
+  unit_names_in(&dir)
+}
+
+fn unit_names_in(dir: &Path) -> Option<HashSet<String>> {
+  match std::fs::read_dir(dir) {
+    Ok(entries) => Some(entries.filter_map(|e| e.ok()?.file_name().into_string().ok()).collect()),
+    // it's only created once there's a transient unit
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Some(HashSet::new()),
+    Err(e) => {
+      warn!("Couldn't read transient units from {}: {}", dir.display(), e);
+      None
+    },
+  }
+}
+
 pub fn get_unit_file_location(service: &UnitId) -> Result<String> {
   // show -P FragmentPath reitunes.service
   let mut args = vec!["--quiet", "show", "-P", "FragmentPath"];
@@ -173,7 +472,7 @@ pub fn get_unit_file_location(service: &UnitId) -> Result<String> {
     args.insert(0, "--user");
   }
 
-  let output = Command::new("systemctl").args(&args).output()?;
+  let output = host_command("systemctl", &args).output()?;
 
   if output.status.success() {
     let path = str::from_utf8(&output.stdout)?.trim();
@@ -187,6 +486,32 @@ pub fn get_unit_file_location(service: &UnitId) -> Result<String> {
   }
 }
 
+/// Every property of a unit, empty ones included, as `Key=value` lines from `systemctl show`
+pub async fn get_all_properties(unit: &UnitId) -> Result<String> {
+  let mut args = vec!["show", "--all", &unit.name];
+  if unit.scope == UnitScope::User {
+    args.insert(0, "--user");
+  }
+  let output = tokio::process::Command::from(host_command("systemctl", args)).output().await?;
+
+  if output.status.success() {
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+  } else {
+    bail!(String::from_utf8_lossy(&output.stderr).trim().to_string());
+  }
+}
+
+// Fail with "cancelled" if `cancel_token` is cancelled before `future` finishes
+async fn cancellable<T>(
+  cancel_token: CancellationToken,
+  future: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+  tokio::select! {
+    _ = cancel_token.cancelled() => bail!("cancelled"),
+    result = future => result,
+  }
+}
+
 pub async fn start_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
   async fn start_service(service: UnitId) -> Result<()> {
     let connection = get_connection(service.scope).await?;
@@ -195,15 +520,7 @@ pub async fn start_service(service: UnitId, cancel_token: CancellationToken) ->
     Ok(())
   }
 
-  // god these select macros are ugly, is there really no better way to select?
-  tokio::select! {
-    _ = cancel_token.cancelled() => {
-        anyhow::bail!("cancelled");
-    }
-    result = start_service(service) => {
-        result
-    }
-  }
+  cancellable(cancel_token, start_service(service)).await
 }
 
 pub async fn stop_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
@@ -214,15 +531,19 @@ pub async fn stop_service(service: UnitId, cancel_token: CancellationToken) -> R
     Ok(())
   }
 
-  // god these select macros are ugly, is there really no better way to select?
-  tokio::select! {
-    _ = cancel_token.cancelled() => {
-        anyhow::bail!("cancelled");
-    }
-    result = stop_service(service) => {
-        result
-    }
+  cancellable(cancel_token, stop_service(service)).await
+}
+
+/// Reload a single unit (e.g. to make it re-read its own config), not to be confused with a daemon-reload
+pub async fn reload_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
+  async fn reload_unit(service: UnitId) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.reload_unit(service.name, "replace".into()).await?;
+    Ok(())
   }
+
+  cancellable(cancel_token, reload_unit(service)).await
 }
 
 pub async fn reload(scope: UnitScope, cancel_token: CancellationToken) -> Result<()> {
@@ -237,21 +558,354 @@ pub async fn reload(scope: UnitScope, cancel_token: CancellationToken) -> Result
     Ok(())
   }
 
-  // god these select macros are ugly, is there really no better way to select?
-  tokio::select! {
-    _ = cancel_token.cancelled() => {
-        anyhow::bail!("cancelled");
-    }
-    result = reload_(scope) => {
-        result
+  cancellable(cancel_token, reload_(scope)).await
+}
+
+pub async fn enable_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
+  async fn enable(service: UnitId) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    let (carries_install_info, changes) =
+      manager_proxy.enable_unit_files(vec![service.name.clone()], false, false).await?;
+    if !carries_install_info && changes.is_empty() {
+      bail!("{} has no [Install] section, so there is nothing to enable", service.name);
     }
+    // like `systemctl enable`, reload so the change takes effect
+    manager_proxy.reload().await?;
+    Ok(())
   }
+
+  cancellable(cancel_token, enable(service)).await
 }
 
-async fn get_connection(scope: UnitScope) -> Result<Connection, anyhow::Error> {
-  match scope {
-    UnitScope::Global => Ok(Connection::system().await?),
-    UnitScope::User => Ok(Connection::session().await?),
+pub async fn disable_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
+  async fn disable(service: UnitId) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.disable_unit_files(vec![service.name], false).await?;
+    // like `systemctl disable`, reload so the change takes effect
+    manager_proxy.reload().await?;
+    Ok(())
+  }
+
+  cancellable(cancel_token, disable(service)).await
+}
+
+pub async fn mask_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
+  async fn mask(service: UnitId) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.mask_unit_files(vec![service.name], false, false).await?;
+    // like `systemctl mask`, reload so the change takes effect
+    manager_proxy.reload().await?;
+    Ok(())
+  }
+
+  cancellable(cancel_token, mask(service)).await
+}
+
+pub async fn reset_failed(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
+  async fn reset(service: UnitId) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.reset_failed_unit(service.name).await?;
+    Ok(())
+  }
+
+  cancellable(cancel_token, reset(service)).await
+}
+
+pub async fn freeze_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
+  async fn freeze(service: UnitId) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    let result = manager_proxy.freeze_unit(service.name.clone()).await;
+    with_freezer_hint(result, "freeze", &service.name)
+  }
+
+  cancellable(cancel_token, freeze(service)).await
+}
+
+pub async fn thaw_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
+  async fn thaw(service: UnitId) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    let result = manager_proxy.thaw_unit(service.name.clone()).await;
+    with_freezer_hint(result, "thaw", &service.name)
+  }
+
+  cancellable(cancel_token, thaw(service)).await
+}
+
+// systemd says NotSupported when the freezer isn't available, which means no cgroup v2
+fn with_freezer_hint(result: zbus::Result<()>, verb: &str, unit: &str) -> Result<()> {
+  let error = match result {
+    Ok(()) => return Ok(()),
+    Err(e) => anyhow::Error::from(e),
+  };
+  if dbus_error_name(&error).as_deref() == Some("org.freedesktop.DBus.Error.NotSupported") {
+    return Err(error.context(format!(
+      "Couldn't {} {}. Freezing units needs the unified cgroup hierarchy (cgroup v2), which this system isn't using.",
+      verb, unit
+    )));
+  }
+  Err(error)
+}
+
+pub async fn unmask_service(service: UnitId, cancel_token: CancellationToken) -> Result<()> {
+  async fn unmask(service: UnitId) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.unmask_unit_files(vec![service.name], false).await?;
+    // like `systemctl unmask`, reload so the change takes effect
+    manager_proxy.reload().await?;
+    Ok(())
+  }
+
+  cancellable(cancel_token, unmask(service)).await
+}
+
+/// Send a signal to a unit's processes. `whom` is "main", "control", or "all" (the whole cgroup)
+pub async fn kill_service(service: UnitId, whom: &str, signal: i32, cancel_token: CancellationToken) -> Result<()> {
+  async fn kill(service: UnitId, whom: String, signal: i32) -> Result<()> {
+    let connection = get_connection(service.scope).await?;
+    let manager_proxy = ManagerProxy::new(&connection).await?;
+    manager_proxy.kill_unit(service.name, whom, signal).await?;
+    Ok(())
+  }
+
+  cancellable(cancel_token, kill(service, whom.to_string(), signal)).await
+}
+
+/// The D-Bus error name (e.g. `org.freedesktop.systemd1.NoSuchUnit`) behind an error, if there is one
+pub fn dbus_error_name(error: &anyhow::Error) -> Option<String> {
+  match error.downcast_ref::<zbus::Error>()? {
+    zbus::Error::MethodError(name, _, _) => Some(name.to_string()),
+    zbus::Error::FDO(e) => Some(e.name().to_string()),
+    _ => None,
+  }
+}
+
+/// Whether a D-Bus error means we need more privileges to do the thing
+pub fn is_permission_error(error_name: &str) -> bool {
+  matches!(
+    error_name,
+    "org.freedesktop.DBus.Error.AccessDenied" | "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired"
+  )
+}
+
+/// Advice for the user about common D-Bus errors
+pub fn dbus_error_hint(error_name: &str) -> Option<&'static str> {
+  match error_name {
+    "org.freedesktop.systemd1.NoSuchUnit" => Some("The unit may have been removed."),
+    "org.freedesktop.systemd1.UnitMasked" => Some("The unit is masked, unmask it first."),
+    "org.freedesktop.systemd1.JobTypeNotApplicable" => {
+      Some("The unit doesn't support this operation (e.g. it may not support reloading), try restarting it instead.")
+    },
+    "org.freedesktop.DBus.Error.AccessDenied" => Some("Try running this tool with sudo."),
+    "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired" => {
+      Some("polkit needs interactive authorization for this. Try running this tool with sudo.")
+    },
+    "org.freedesktop.DBus.Error.NoReply" | "org.freedesktop.DBus.Error.Timeout" => {
+      Some("systemd didn't respond in time. The job may still be running, check the unit's status.")
+    },
+    _ => None,
+  }
+}
+
+// Set once at startup by `connect_to_host`
+static REMOTE: OnceLock<Remote> = OnceLock::new();
+
+struct Remote {
+  host: String,
+  // kept as the error message when a bus couldn't be reached, so every call fails the same way a local one would
+  system_bus: Result<Connection, String>,
+  session_bus: Result<Connection, String>,
+}
+
+/// The host given with `--host`, if we're managing another machine's systemd over ssh
+pub fn remote_host() -> Option<&'static str> {
+  REMOTE.get().map(|r| r.host.as_str())
+}
+
+/// Connect to `host`'s buses over ssh. Everything after this (D-Bus calls, journalctl, systemctl) goes to `host`.
+/// Connecting happens up front so ssh can prompt for a password before the TUI takes over the terminal
+pub async fn connect_to_host(host: &str, scope: Scope) -> Result<()> {
+  let not_needed = |bus: &str| {
+    Err(format!("Not connected to the {} D-Bus on {} with --scope {}", bus, host, scope.name().to_lowercase()))
+  };
+  let system_bus = match scope {
+    Scope::User => not_needed("system"),
+    Scope::Global | Scope::All => Ok(connect_over_ssh(host, UnitScope::Global).await?),
+  };
+  let session_bus = match scope {
+    Scope::Global => not_needed("session"),
+    Scope::User => Ok(connect_over_ssh(host, UnitScope::User).await?),
+    // like a local session bus, it's fine for this one to be missing. System units are still shown
+    Scope::All => connect_over_ssh(host, UnitScope::User).await.map_err(|e| format!("{:#}", e)),
+  };
+  if REMOTE.set(Remote { host: host.to_string(), system_bus, session_bus }).is_err() {
+    bail!("Already connected to {}", remote_host().unwrap_or_default());
+  }
+  Ok(())
+}
+
+// What `systemctl --host` does: systemd-stdio-bridge on the other end forwards its stdin/stdout to the bus there
+async fn connect_over_ssh(host: &str, scope: UnitScope) -> Result<Connection> {
+  let bus = match scope {
+    UnitScope::Global => "system",
+    UnitScope::User => "session",
+  };
+  let (ours, theirs) = std::os::unix::net::UnixStream::pair()?;
+  let mut command = tokio::process::Command::new("ssh");
+  command.args(["-xT", "--", host, "systemd-stdio-bridge"]);
+  if scope == UnitScope::User {
+    command.arg("--user");
+  }
+  command.stdin(OwnedFd::from(theirs.try_clone()?)).stdout(OwnedFd::from(theirs)).stderr(Stdio::piped());
+  let mut child = command.spawn().context("Failed to run ssh")?;
+  // the command holds on to ssh's end of the socket. Without dropping it we'd never see EOF if ssh exits
+  drop(command);
+  let stderr = child.stderr.take().unwrap();
+
+  ours.set_nonblocking(true)?;
+  let connection = zbus::connection::Builder::unix_stream(tokio::net::UnixStream::from_std(ours)?)
+    // the bridge accepts anonymous clients. EXTERNAL can get rejected, our uid means nothing over there
+    .auth_mechanisms(&[zbus::AuthMechanism::External, zbus::AuthMechanism::Anonymous])
+    .build()
+    .await;
+
+  match connection {
+    Ok(connection) => {
+      // anything ssh says later (e.g. the connection dropping) would be drawn over the TUI, log it instead
+      let mut lines = tokio::io::BufReader::new(stderr).lines();
+      tokio::spawn(async move {
+        while let Ok(Some(line)) = lines.next_line().await {
+          warn!("ssh: {}", line);
+        }
+      });
+      Ok(connection)
+    },
+    Err(e) => {
+      let _ = child.start_kill();
+      let mut ssh_error = String::new();
+      let _ = tokio::io::BufReader::new(stderr).read_to_string(&mut ssh_error).await;
+      let ssh_error = match ssh_error.trim() {
+        "" => String::new(),
+        ssh_error => format!(" ssh said: {}.", ssh_error),
+      };
+      bail!(
+        "Could not connect to the {} D-Bus on {} over ssh.{} Check that `ssh {}` works and that \
+         systemd-stdio-bridge (part of systemd) is installed there: {}",
+        bus,
+        host,
+        ssh_error,
+        host,
+        e
+      )
+    },
+  }
+}
+
+/// `program` with `args`, run on the `--host` over ssh if there is one. For commands that run in the background,
+/// their stdin is /dev/null so ssh doesn't eat keypresses meant for the TUI
+pub fn host_command<S: AsRef<str>>(program: &str, args: impl IntoIterator<Item = S>) -> Command {
+  ssh_command("-n", program, args)
+}
+
+/// Like `host_command`, for commands that take over the terminal (a pager, sudo's password prompt)
+pub fn interactive_host_command<S: AsRef<str>>(program: &str, args: impl IntoIterator<Item = S>) -> Command {
+  ssh_command("-t", program, args)
+}
+
+fn ssh_command<S: AsRef<str>>(ssh_flag: &str, program: &str, args: impl IntoIterator<Item = S>) -> Command {
+  let args: Vec<S> = args.into_iter().collect();
+  let Some(host) = remote_host() else {
+    let mut command = Command::new(program);
+    command.args(args.iter().map(AsRef::as_ref));
+    return command;
+  };
+  // ssh joins its arguments into one string for the remote shell, so they need quoting or `;` in a journal cursor
+  // would end the command
+  let remote_command =
+    std::iter::once(program).chain(args.iter().map(AsRef::as_ref)).map(shell_quote).collect::<Vec<_>>().join(" ");
+  let mut command = Command::new("ssh");
+  command.args([ssh_flag, "--", host, &remote_command]);
+  command
+}
+
+fn shell_quote(arg: &str) -> String {
+  let safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c);
+  if !arg.is_empty() && arg.chars().all(safe) {
+    arg.to_string()
+  } else {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+  }
+}
+
+/// What privileged retries run systemctl with. pkexec needs a polkit agent, and there's none on the other end of ssh.
+/// sudo can prompt over it
+pub fn elevate_command() -> &'static str {
+  if remote_host().is_some() {
+    "sudo"
+  } else {
+    "pkexec"
+  }
+}
+
+/// Read a file, on the `--host` if there is one
+pub async fn read_file(path: &str) -> std::io::Result<String> {
+  if remote_host().is_none() {
+    return tokio::fs::read_to_string(path).await;
+  }
+  let output = tokio::process::Command::from(host_command("cat", ["--", path])).output().await?;
+  if output.status.success() {
+    return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+  }
+  let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+  // close enough to what reading it locally would say, callers care about missing files
+  let kind = if stderr.contains("No such file") { std::io::ErrorKind::NotFound } else { std::io::ErrorKind::Other };
+  Err(std::io::Error::new(kind, stderr))
+}
+
+pub async fn get_connection(scope: UnitScope) -> Result<Connection, anyhow::Error> {
+  if let Some(remote) = REMOTE.get() {
+    let bus = match scope {
+      UnitScope::Global => &remote.system_bus,
+      UnitScope::User => &remote.session_bus,
+    };
+    return bus.clone().map_err(anyhow::Error::msg);
+  }
+
+  let connection = match scope {
+    UnitScope::Global => Connection::system().await,
+    UnitScope::User => Connection::session().await,
+  };
+  // flattened to a string because zbus's I/O errors repeat their source in their message, so `{:#}` says it twice
+  connection.map_err(|e| anyhow::anyhow!("{}: {}", connection_error_hint(scope, &e), e))
+}
+
+// The raw zbus errors for these ("No such file or directory", "Address(...)") don't say what to do about them
+fn connection_error_hint(scope: UnitScope, error: &zbus::Error) -> String {
+  let io_kind = match error {
+    zbus::Error::InputOutput(e) => Some(e.kind()),
+    _ => None,
+  };
+  match (scope, io_kind) {
+    (UnitScope::Global, Some(std::io::ErrorKind::PermissionDenied)) => {
+      "Permission denied connecting to the system D-Bus. Try running this tool with sudo".to_string()
+    },
+    (UnitScope::Global, _) => "Could not connect to the system D-Bus. Is systemd running? \
+       In a container you may need to mount the host bus (/run/dbus/system_bus_socket)"
+      .to_string(),
+    (UnitScope::User, _) if matches!(error, zbus::Error::Address(_)) => {
+      "Could not find the session D-Bus for user units. If you switched users with su or sudo, \
+       try `machinectl shell` or logging in directly, or use `--scope global`"
+        .to_string()
+    },
+    (UnitScope::User, _) => "Could not connect to the session D-Bus for user units. \
+       Is a user systemd instance running? Use `--scope global` to only show system units"
+      .to_string(),
   }
 }
 
@@ -263,30 +917,16 @@ pub async fn restart_service(service: UnitId, cancel_token: CancellationToken) -
     Ok(())
   }
 
-  // god these select macros are ugly, is there really no better way to select?
-  tokio::select! {
-    _ = cancel_token.cancelled() => {
-        // The token was cancelled
-        anyhow::bail!("cancelled");
-    }
-    result = restart(service) => {
-        result
-    }
-  }
+  cancellable(cancel_token, restart(service)).await
 }
 
 // useless function only added to test that cancellation works
 pub async fn sleep_test(_service: String, cancel_token: CancellationToken) -> Result<()> {
-  // god these select macros are ugly, is there really no better way to select?
-  tokio::select! {
-      _ = cancel_token.cancelled() => {
-          // The token was cancelled
-          anyhow::bail!("cancelled");
-      }
-      _ = tokio::time::sleep(std::time::Duration::from_secs(2)) => {
-          Ok(())
-      }
-  }
+  cancellable(cancel_token, async {
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    Ok(())
+  })
+  .await
 }
 
 /// Proxy object for `org.freedesktop.systemd1.Manager`.
@@ -314,6 +954,22 @@ pub trait Manager {
   #[dbus_proxy(name = "RestartUnit")]
   fn restart_unit(&self, name: String, mode: String) -> zbus::Result<zvariant::OwnedObjectPath>;
 
+  /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#KillUnit()) Call interface method `KillUnit`.
+  #[dbus_proxy(name = "KillUnit")]
+  fn kill_unit(&self, name: String, whom: String, signal: i32) -> zbus::Result<()>;
+
+  /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#ResetFailedUnit()) Call interface method `ResetFailedUnit`.
+  #[dbus_proxy(name = "ResetFailedUnit")]
+  fn reset_failed_unit(&self, name: String) -> zbus::Result<()>;
+
+  /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#FreezeUnit()) Call interface method `FreezeUnit`.
+  #[dbus_proxy(name = "FreezeUnit")]
+  fn freeze_unit(&self, name: String) -> zbus::Result<()>;
+
+  /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#ThawUnit()) Call interface method `ThawUnit`.
+  #[dbus_proxy(name = "ThawUnit")]
+  fn thaw_unit(&self, name: String) -> zbus::Result<()>;
+
   /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#EnableUnitFiles()) Call interface method `EnableUnitFiles`.
   #[dbus_proxy(name = "EnableUnitFiles")]
   fn enable_unit_files(
@@ -327,6 +983,19 @@ pub trait Manager {
   #[dbus_proxy(name = "DisableUnitFiles")]
   fn disable_unit_files(&self, files: Vec<String>, runtime: bool) -> zbus::Result<Vec<(String, String, String)>>;
 
+  /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#MaskUnitFiles()) Call interface method `MaskUnitFiles`.
+  #[dbus_proxy(name = "MaskUnitFiles")]
+  fn mask_unit_files(
+    &self,
+    files: Vec<String>,
+    runtime: bool,
+    force: bool,
+  ) -> zbus::Result<Vec<(String, String, String)>>;
+
+  /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#UnmaskUnitFiles()) Call interface method `UnmaskUnitFiles`.
+  #[dbus_proxy(name = "UnmaskUnitFiles")]
+  fn unmask_unit_files(&self, files: Vec<String>, runtime: bool) -> zbus::Result<Vec<(String, String, String)>>;
+
   /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#ListUnits()) Call interface method `ListUnits`.
   #[dbus_proxy(name = "ListUnits")]
   fn list_units(
@@ -370,6 +1039,10 @@ pub trait Manager {
   /// [📖](https://www.freedesktop.org/software/systemd/man/systemd.directives.html#Reload()) Call interface method `Reload`.
   #[dbus_proxy(name = "Reload")]
   fn reload(&self) -> zbus::Result<()>;
+
+  /// Get property `Version`.
+  #[dbus_proxy(property)]
+  fn version(&self) -> zbus::Result<String>;
 }
 
 /// Proxy object for `org.freedesktop.systemd1.Unit`.
@@ -385,6 +1058,10 @@ pub trait Unit {
   #[dbus_proxy(property)]
   fn active_state(&self) -> zbus::Result<String>;
 
+  /// Get property `SubState`.
+  #[dbus_proxy(property)]
+  fn sub_state(&self) -> zbus::Result<String>;
+
   /// Get property `LoadState`.
   #[dbus_proxy(property)]
   fn load_state(&self) -> zbus::Result<String>;
@@ -392,6 +1069,54 @@ pub trait Unit {
   /// Get property `UnitFileState`.
   #[dbus_proxy(property)]
   fn unit_file_state(&self) -> zbus::Result<String>;
+
+  /// Get property `DropInPaths`.
+  #[dbus_proxy(property)]
+  fn drop_in_paths(&self) -> zbus::Result<Vec<String>>;
+
+  /// Get property `ActiveEnterTimestamp`.
+  #[dbus_proxy(property)]
+  fn active_enter_timestamp(&self) -> zbus::Result<u64>;
+
+  /// Get property `InactiveEnterTimestamp`.
+  #[dbus_proxy(property)]
+  fn inactive_enter_timestamp(&self) -> zbus::Result<u64>;
+
+  /// Get property `FreezerState`.
+  #[dbus_proxy(property)]
+  fn freezer_state(&self) -> zbus::Result<String>;
+
+  /// Get property `Transient`.
+  #[dbus_proxy(property)]
+  fn transient(&self) -> zbus::Result<bool>;
+
+  /// Get property `InvocationID`.
+  #[dbus_proxy(property, name = "InvocationID")]
+  fn invocation_id(&self) -> zbus::Result<Vec<u8>>;
+
+  /// Get property `Requires`.
+  #[dbus_proxy(property)]
+  fn requires(&self) -> zbus::Result<Vec<String>>;
+
+  /// Get property `Wants`.
+  #[dbus_proxy(property)]
+  fn wants(&self) -> zbus::Result<Vec<String>>;
+
+  /// Get property `RequiredBy`.
+  #[dbus_proxy(property)]
+  fn required_by(&self) -> zbus::Result<Vec<String>>;
+
+  /// Get property `WantedBy`.
+  #[dbus_proxy(property)]
+  fn wanted_by(&self) -> zbus::Result<Vec<String>>;
+
+  /// Get property `After`.
+  #[dbus_proxy(property)]
+  fn after(&self) -> zbus::Result<Vec<String>>;
+
+  /// Get property `Before`.
+  #[dbus_proxy(property)]
+  fn before(&self) -> zbus::Result<Vec<String>>;
 }
 
 /// Proxy object for `org.freedesktop.systemd1.Service`.
@@ -406,6 +1131,74 @@ trait Service {
   /// Get property `MainPID`.
   #[dbus_proxy(property, name = "MainPID")]
   fn main_pid(&self) -> zbus::Result<u32>;
+
+  /// Get property `MemoryCurrent`.
+  #[dbus_proxy(property)]
+  fn memory_current(&self) -> zbus::Result<u64>;
+
+  /// Get property `CPUUsageNSec`.
+  #[dbus_proxy(property, name = "CPUUsageNSec")]
+  fn cpu_usage_n_sec(&self) -> zbus::Result<u64>;
+
+  /// Get property `NRestarts`.
+  #[dbus_proxy(property, name = "NRestarts")]
+  fn n_restarts(&self) -> zbus::Result<u32>;
+
+  /// Get property `Environment`.
+  #[dbus_proxy(property)]
+  fn environment(&self) -> zbus::Result<Vec<String>>;
+
+  /// Get property `EnvironmentFiles`.
+  #[dbus_proxy(property)]
+  fn environment_files(&self) -> zbus::Result<Vec<(String, bool)>>;
+}
+
+/// Proxy object for `org.freedesktop.systemd1.Socket`.
+/// Taken from https://github.com/lucab/zbus_systemd/blob/main/src/systemd1/generated.rs
+#[proxy(
+  interface = "org.freedesktop.systemd1.Socket",
+  default_service = "org.freedesktop.systemd1",
+  assume_defaults = false,
+  gen_blocking = false
+)]
+trait Socket {
+  /// Get property `Listen`.
+  #[dbus_proxy(property)]
+  fn listen(&self) -> zbus::Result<Vec<(String, String)>>;
+
+  /// Get property `Accept`.
+  #[dbus_proxy(property)]
+  fn accept(&self) -> zbus::Result<bool>;
+
+  /// Get property `NConnections`.
+  #[dbus_proxy(property, name = "NConnections")]
+  fn n_connections(&self) -> zbus::Result<u32>;
+}
+
+/// Proxy object for `org.freedesktop.systemd1.Timer`.
+/// Taken from https://github.com/lucab/zbus_systemd/blob/main/src/systemd1/generated.rs
+#[proxy(
+  interface = "org.freedesktop.systemd1.Timer",
+  default_service = "org.freedesktop.systemd1",
+  assume_defaults = false,
+  gen_blocking = false
+)]
+trait Timer {
+  /// Get property `NextElapseUSecRealtime`.
+  #[dbus_proxy(property, name = "NextElapseUSecRealtime")]
+  fn next_elapse_u_sec_realtime(&self) -> zbus::Result<u64>;
+
+  /// Get property `NextElapseUSecMonotonic`.
+  #[dbus_proxy(property, name = "NextElapseUSecMonotonic")]
+  fn next_elapse_u_sec_monotonic(&self) -> zbus::Result<u64>;
+
+  /// Get property `LastTriggerUSec`.
+  #[dbus_proxy(property, name = "LastTriggerUSec")]
+  fn last_trigger_u_sec(&self) -> zbus::Result<u64>;
+
+  /// Get property `Unit`.
+  #[dbus_proxy(property)]
+  fn unit(&self) -> zbus::Result<String>;
 }
 
 /// Returns the load state of a systemd unit
@@ -450,6 +1243,201 @@ pub async fn get_unit_file_state(connection: &Connection, full_service_name: &st
   }
 }
 
+/// Returns when a timer will next fire and when it last fired, or `None` for never/unknown
+pub async fn get_timer_times(timer: &UnitId) -> Result<(Option<SystemTime>, Option<SystemTime>)> {
+  let connection = get_connection(timer.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&timer.name))?;
+  let timer_proxy = TimerProxy::new(&connection, path).await?;
+
+  let realtime = |usec: u64| (usec != 0).then(|| SystemTime::UNIX_EPOCH + Duration::from_micros(usec));
+
+  let next_elapse = match timer_proxy.next_elapse_u_sec_realtime().await? {
+    // timers that only have monotonic triggers (e.g. OnBootSec) report their next elapse on the monotonic clock
+    0 => monotonic_to_realtime(timer_proxy.next_elapse_u_sec_monotonic().await?),
+    usec => realtime(usec),
+  };
+  let last_trigger = realtime(timer_proxy.last_trigger_u_sec().await?);
+
+  Ok((next_elapse, last_trigger))
+}
+
+/// Returns every timer in `scope`, soonest to fire first. Timers that aren't scheduled go last
+pub async fn get_timers(scope: Scope) -> Result<Vec<TimerInfo>> {
+  let timers = get_all_services(scope, &["*.timer".into()]).await?;
+
+  let mut pending: FuturesUnordered<_> = timers
+    .into_iter()
+    .map(|timer| async move {
+      let timer = timer.id();
+      let (next_elapse, last_trigger) = get_timer_times(&timer).await?;
+      let connection = get_connection(timer.scope).await?;
+      let path = zvariant::ObjectPath::try_from(get_unit_path(&timer.name))?;
+      let activates = TimerProxy::new(&connection, path).await?.unit().await?;
+      anyhow::Ok(TimerInfo { timer, next_elapse, last_trigger, activates })
+    })
+    .collect();
+
+  let mut infos = vec![];
+  while let Some(info) = pending.next().await {
+    infos.push(info?);
+  }
+  infos.sort_by_key(|info| (info.next_elapse.is_none(), info.next_elapse));
+  Ok(infos)
+}
+
+/// Returns when a unit last became active and inactive. `None` if it hasn't since boot
+pub async fn get_state_change_times(unit: &UnitId) -> Result<(Option<SystemTime>, Option<SystemTime>)> {
+  let connection = get_connection(unit.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&unit.name))?;
+  let unit_proxy = UnitProxy::new(&connection, path).await?;
+
+  let realtime = |usec: u64| (usec != 0).then(|| SystemTime::UNIX_EPOCH + Duration::from_micros(usec));
+  let active_enter = realtime(unit_proxy.active_enter_timestamp().await?);
+  let inactive_enter = realtime(unit_proxy.inactive_enter_timestamp().await?);
+
+  Ok((active_enter, inactive_enter))
+}
+
+/// Returns whether a unit's processes are frozen: `running`, `frozen`, `freezing`, or `thawing`
+pub async fn get_freezer_state(unit: &UnitId) -> Result<String> {
+  let connection = get_connection(unit.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&unit.name))?;
+  let unit_proxy = UnitProxy::new(&connection, path).await?;
+  Ok(unit_proxy.freezer_state().await?)
+}
+
+/// A unit's current activation state and sub state, e.g. `("activating", "start-pre")`
+pub async fn get_unit_state(unit: &UnitId) -> Result<(String, String)> {
+  let connection = get_connection(unit.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&unit.name))?;
+  let unit_proxy = UnitProxy::new(&connection, path).await?;
+  Ok((unit_proxy.active_state().await?, unit_proxy.sub_state().await?))
+}
+
+/// The ID of the unit's current (or most recent) run, as 32 hex digits like `journalctl _SYSTEMD_INVOCATION_ID=`
+/// expects. `None` if the unit hasn't run since it was loaded
+pub async fn get_invocation_id(unit: &UnitId) -> Result<Option<String>> {
+  let connection = get_connection(unit.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&unit.name))?;
+  let unit_proxy = UnitProxy::new(&connection, path).await?;
+  let id = unit_proxy.invocation_id().await?;
+  if id.iter().all(|b| *b == 0) {
+    return Ok(None);
+  }
+  Ok(Some(id.iter().map(|b| format!("{:02x}", b)).collect()))
+}
+
+pub async fn get_transient(unit: &UnitId) -> Result<bool> {
+  let connection = get_connection(unit.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&unit.name))?;
+  let unit_proxy = UnitProxy::new(&connection, path).await?;
+  Ok(unit_proxy.transient().await?)
+}
+
+pub async fn get_drop_in_paths(unit: &UnitId) -> Result<Vec<String>> {
+  let connection = get_connection(unit.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&unit.name))?;
+  let unit_proxy = UnitProxy::new(&connection, path).await?;
+  Ok(unit_proxy.drop_in_paths().await?)
+}
+
+/// Returns a unit's dependencies (and reverse dependencies), grouped by kind. Empty groups are left out
+pub async fn get_dependencies(unit: &UnitId) -> Result<Vec<(String, Vec<String>)>> {
+  let connection = get_connection(unit.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&unit.name))?;
+  let unit_proxy = UnitProxy::new(&connection, path).await?;
+
+  let groups = vec![
+    ("Requires", unit_proxy.requires().await?),
+    ("Wants", unit_proxy.wants().await?),
+    ("Required by", unit_proxy.required_by().await?),
+    ("Wanted by", unit_proxy.wanted_by().await?),
+    ("After", unit_proxy.after().await?),
+    ("Before", unit_proxy.before().await?),
+  ];
+
+  Ok(groups.into_iter().filter(|(_, units)| !units.is_empty()).map(|(name, units)| (name.to_string(), units)).collect())
+}
+
+/// Returns what a socket unit listens on and how it handles connections
+pub async fn get_socket_info(socket: &UnitId) -> Result<SocketInfo> {
+  let connection = get_connection(socket.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&socket.name))?;
+  let socket_proxy = SocketProxy::new(&connection, path).await?;
+
+  Ok(SocketInfo {
+    listen: socket_proxy.listen().await?,
+    accept: socket_proxy.accept().await?,
+    connections: socket_proxy.n_connections().await?,
+  })
+}
+
+pub async fn get_environment(service: &UnitId) -> Result<ServiceEnvironment> {
+  let connection = get_connection(service.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&service.name))?;
+  let service_proxy = ServiceProxy::new(&connection, path).await?;
+
+  Ok(ServiceEnvironment {
+    variables: service_proxy.environment().await?,
+    files: service_proxy.environment_files().await?,
+  })
+}
+
+/// Returns a service's current memory usage in bytes and total CPU time, or `None` if accounting is off
+pub async fn get_resource_usage(service: &UnitId) -> Result<(Option<u64>, Option<Duration>)> {
+  let connection = get_connection(service.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&service.name))?;
+  let service_proxy = ServiceProxy::new(&connection, path).await?;
+
+  // systemd reports u64::MAX when a value isn't available
+  let memory_current = Some(service_proxy.memory_current().await?).filter(|&bytes| bytes != u64::MAX);
+  let cpu_usage =
+    Some(service_proxy.cpu_usage_n_sec().await?).filter(|&nsec| nsec != u64::MAX).map(Duration::from_nanos);
+
+  Ok((memory_current, cpu_usage))
+}
+
+/// Returns how many times a service has been automatically restarted (`Restart=`) since it was last started by hand
+pub async fn get_restart_count(service: &UnitId) -> Result<u32> {
+  let connection = get_connection(service.scope).await?;
+  let path = zvariant::ObjectPath::try_from(get_unit_path(&service.name))?;
+  let service_proxy = ServiceProxy::new(&connection, path).await?;
+  Ok(service_proxy.n_restarts().await?)
+}
+
+/// Whether the system was booted with systemd as its init system. Same check as `sd_booted()`
+pub fn booted_with_systemd() -> bool {
+  std::fs::symlink_metadata("/run/systemd/system").is_ok_and(|m| m.is_dir())
+}
+
+/// The version of the systemd instance managing units in `scope`, e.g. `255.4-1ubuntu8`
+pub async fn get_systemd_version(scope: UnitScope) -> Result<String> {
+  let connection = get_connection(scope).await?;
+  let manager_proxy = ManagerProxy::new(&connection).await?;
+  Ok(manager_proxy.version().await?)
+}
+
+/// Convert a CLOCK_MONOTONIC timestamp in microseconds (as systemd reports them) to wall clock time
+fn monotonic_to_realtime(usec: u64) -> Option<SystemTime> {
+  if usec == 0 || usec == u64::MAX {
+    return None;
+  }
+
+  let mut now = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+  // SAFETY: clock_gettime only writes to the timespec we pass in
+  if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) } != 0 {
+    return None;
+  }
+  let now_monotonic = Duration::new(now.tv_sec as u64, now.tv_nsec as u32);
+  let target = Duration::from_micros(usec);
+
+  if target >= now_monotonic {
+    Some(SystemTime::now() + (target - now_monotonic))
+  } else {
+    Some(SystemTime::now() - (now_monotonic - target))
+  }
+}
+
 /// Returns the PID of a systemd service
 ///
 /// # Arguments
@@ -488,3 +1476,62 @@ fn encode_as_dbus_object_path(input_string: &str) -> String {
 pub fn get_unit_path(full_service_name: &str) -> String {
   format!("/org/freedesktop/systemd1/unit/{}", encode_as_dbus_object_path(full_service_name))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn unit(name: &str) -> UnitWithStatus {
+    let path = zvariant::OwnedObjectPath::try_from("/").unwrap();
+    let raw = (
+      name.to_string(),
+      String::new(),
+      "loaded".to_string(),
+      "active".to_string(),
+      "running".to_string(),
+      String::new(),
+      path.clone(),
+      0,
+      String::new(),
+      path,
+    );
+    to_unit_status(raw, UnitScope::Global)
+  }
+
+  #[test]
+  fn shell_quote() {
+    assert_eq!(super::shell_quote("--since=2024-01-01"), "--since=2024-01-01");
+    assert_eq!(super::shell_quote(""), "''");
+
+    // what the remote shell gets back out of them
+    let args = ["--after-cursor=s=1;i=2", "it's", "$HOME `id`", "a\\b", " "];
+    let script = format!("printf '%s\\n' {}", args.map(super::shell_quote).join(" "));
+    let output = Command::new("sh").args(["-c", &script]).output().unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), args.map(|a| format!("{}\n", a)).concat());
+  }
+
+  #[test]
+  fn unit_names_in() {
+    let dir = std::env::temp_dir().join(format!("systemctl-tui-transient-{}", std::process::id()));
+    assert_eq!(super::unit_names_in(&dir), Some(HashSet::new()));
+
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("run-u42.service"), "").unwrap();
+    std::fs::write(dir.join("run-u43.scope"), "").unwrap();
+    let names = super::unit_names_in(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(names, Some(HashSet::from(["run-u42.service".to_string(), "run-u43.scope".to_string()])));
+  }
+
+  #[test]
+  fn template_instance() {
+    assert_eq!(unit("getty@tty1.service").template_instance(), Some(("getty@.service".into(), "tty1")));
+    // only the last dot separates the type
+    assert_eq!(
+      unit("systemd-fsck@dev-disk-by\\x2duuid-1234.abc.service").template_instance(),
+      Some(("systemd-fsck@.service".into(), "dev-disk-by\\x2duuid-1234.abc"))
+    );
+    assert_eq!(unit("getty@.service").template_instance(), None);
+    assert_eq!(unit("sshd.service").template_instance(), None);
+  }
+}
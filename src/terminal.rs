@@ -1,6 +1,10 @@
 use std::{
+  io::Write,
   ops::{Deref, DerefMut},
-  sync::Arc,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
 };
 
 use anyhow::{anyhow, Context, Result};
@@ -18,14 +22,53 @@ use tokio::{
 
 use crate::components::{home::Home, Component};
 
+/// Which stream the TUI is drawn on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputStream {
+  // stderr by default so that stdout is free for non-interactive output
+  #[default]
+  Stderr,
+  Stdout,
+}
+
+// Global because the panic and signal handlers need to be able to restore the terminal too
+static RENDER_TO_STDOUT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_output_stream(stream: OutputStream) {
+  RENDER_TO_STDOUT.store(stream == OutputStream::Stdout, Ordering::Relaxed);
+}
+
+/// Writes to whichever stream was chosen with `set_output_stream`
+pub struct Output;
+
+impl Write for Output {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    if RENDER_TO_STDOUT.load(Ordering::Relaxed) {
+      std::io::stdout().write(buf)
+    } else {
+      std::io::stderr().write(buf)
+    }
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    if RENDER_TO_STDOUT.load(Ordering::Relaxed) {
+      std::io::stdout().flush()
+    } else {
+      std::io::stderr().flush()
+    }
+  }
+}
+
 // A struct that mostly exists to be a catch-all for terminal operations that should be synchronized
 pub struct Tui {
-  pub terminal: ratatui::Terminal<Backend<std::io::Stderr>>,
+  pub terminal: ratatui::Terminal<Backend<Output>>,
+  /// Capture the mouse for clicking and scrolling. Without it, the terminal handles the mouse (e.g. to select text)
+  mouse_capture: bool,
 }
 
 impl Tui {
-  pub fn new() -> Result<Self> {
-    let terminal = ratatui::Terminal::new(Backend::new(std::io::stderr()))?;
+  pub fn new(mouse_capture: bool) -> Result<Self> {
+    let terminal = ratatui::Terminal::new(Backend::new(Output))?;
 
     // spin up a signal handler to catch SIGTERM and exit gracefully
     let _ = std::thread::spawn(move || {
@@ -36,12 +79,15 @@ impl Tui {
       low_level::emulate_default_handler(signal).unwrap();
     });
 
-    Ok(Self { terminal })
+    Ok(Self { terminal, mouse_capture })
   }
 
   pub fn enter(&self) -> Result<()> {
     crossterm::terminal::enable_raw_mode()?;
-    crossterm::execute!(std::io::stderr(), EnterAlternateScreen, EnableMouseCapture, cursor::Hide)?;
+    crossterm::execute!(Output, EnterAlternateScreen, cursor::Hide)?;
+    if self.mouse_capture {
+      crossterm::execute!(Output, EnableMouseCapture)?;
+    }
     Ok(())
   }
 
@@ -64,13 +110,13 @@ impl Tui {
 
 // This one's public because we want to expose it to the panic handler
 pub fn exit() -> Result<()> {
-  crossterm::execute!(std::io::stderr(), LeaveAlternateScreen, DisableMouseCapture, cursor::Show)?;
+  crossterm::execute!(Output, LeaveAlternateScreen, DisableMouseCapture, cursor::Show)?;
   crossterm::terminal::disable_raw_mode()?;
   Ok(())
 }
 
 impl Deref for Tui {
-  type Target = ratatui::Terminal<Backend<std::io::Stderr>>;
+  type Target = ratatui::Terminal<Backend<Output>>;
 
   fn deref(&self) -> &Self::Target {
     &self.terminal
@@ -104,10 +150,10 @@ pub struct TerminalHandler {
 }
 
 impl TerminalHandler {
-  pub fn new(home: Arc<Mutex<Home>>) -> Self {
+  pub fn new(home: Arc<Mutex<Home>>, mouse_capture: bool) -> Self {
     let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
     let cloned_home = home.clone();
-    let tui = Tui::new().context(anyhow!("Unable to create terminal")).unwrap();
+    let tui = Tui::new(mouse_capture).context(anyhow!("Unable to create terminal")).unwrap();
     tui.enter().unwrap();
     let tui = Arc::new(Mutex::new(tui));
     let cloned_tui = tui.clone();
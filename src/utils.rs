@@ -1,16 +1,22 @@
-use std::{io::Write, path::PathBuf, sync::atomic::AtomicBool};
+use std::{
+  collections::VecDeque,
+  io::Write,
+  path::{Path, PathBuf},
+  sync::{atomic::AtomicBool, Mutex},
+};
 
 use anyhow::{anyhow, Context, Result};
 use better_panic::Settings;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
-use tracing::{error, level_filters::LevelFilter};
+use tracing::{error, field::Visit, level_filters::LevelFilter, Level, Subscriber};
 use tracing_appender::{
   non_blocking::WorkerGuard,
   rolling::{RollingFileAppender, Rotation},
 };
 use tracing_subscriber::{
-  self, filter::EnvFilter, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, Layer,
+  self, filter::EnvFilter, layer::Context as LayerContext, prelude::__tracing_subscriber_SubscriberExt,
+  util::SubscriberInitExt, Layer,
 };
 
 lazy_static! {
@@ -23,6 +29,67 @@ lazy_static! {
 
 static TRACING_ENABLED: AtomicBool = AtomicBool::new(false);
 
+// How many warnings/errors to keep around for the status line
+const STATUS_MESSAGE_CAPACITY: usize = 50;
+
+lazy_static! {
+  static ref STATUS_MESSAGES: Mutex<VecDeque<StatusMessage>> =
+    Mutex::new(VecDeque::with_capacity(STATUS_MESSAGE_CAPACITY));
+}
+
+/// A warning or error logged by the app, kept in memory so it can be shown without opening the logger pane
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+  pub level: Level,
+  pub time: chrono::DateTime<chrono::Local>,
+  pub message: String,
+}
+
+/// The most recent warning/error logged by the app, if any
+pub fn latest_status_message() -> Option<StatusMessage> {
+  STATUS_MESSAGES.lock().ok()?.back().cloned()
+}
+
+// A tracing layer that records warnings and errors into a small ring buffer
+struct StatusMessageLayer;
+
+impl<S: Subscriber> Layer<S> for StatusMessageLayer {
+  fn on_event(&self, event: &tracing::Event<'_>, _ctx: LayerContext<'_, S>) {
+    let level = *event.metadata().level();
+    // more verbose levels compare as greater
+    if level > Level::WARN {
+      return;
+    }
+
+    struct MessageVisitor(String);
+    impl Visit for MessageVisitor {
+      fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "message" {
+          self.0 = value.to_string();
+        }
+      }
+
+      fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+          self.0 = format!("{value:?}");
+        }
+      }
+    }
+
+    let mut visitor = MessageVisitor(String::new());
+    event.record(&mut visitor);
+    // messages can span multiple lines (e.g. error popups), only the first fits in the status line
+    let message = visitor.0.lines().next().unwrap_or_default().to_string();
+
+    if let Ok(mut messages) = STATUS_MESSAGES.lock() {
+      if messages.len() == STATUS_MESSAGE_CAPACITY {
+        messages.pop_front();
+      }
+      messages.push_back(StatusMessage { level, time: chrono::Local::now(), message });
+    }
+  }
+}
+
 pub fn initialize_panic_handler() {
   std::panic::set_hook(Box::new(|panic_info| {
     if let Err(r) = crate::terminal::exit() {
@@ -34,6 +101,65 @@ pub fn initialize_panic_handler() {
   }));
 }
 
+/// Format a duration compactly for humans, e.g. `3h 12m` or `45s`. Only the 2 most significant units are shown
+pub fn format_duration(duration: std::time::Duration) -> String {
+  let secs = duration.as_secs();
+  let (days, hours, minutes, seconds) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+  if days > 0 {
+    format!("{days}d {hours}h")
+  } else if hours > 0 {
+    format!("{hours}h {minutes}m")
+  } else if minutes > 0 {
+    format!("{minutes}m {seconds}s")
+  } else {
+    format!("{seconds}s")
+  }
+}
+
+/// Format a number of bytes for humans, e.g. `12.3 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+  if bytes < 1024 {
+    return format!("{bytes} B");
+  }
+  let mut value = bytes as f64 / 1024.0;
+  let mut unit = 0;
+  while value >= 1024.0 && unit < UNITS.len() - 1 {
+    value /= 1024.0;
+    unit += 1;
+  }
+  format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Format a point in time relative to now, e.g. `in 3h 12m` or `5m 2s ago`
+pub fn format_relative_time(time: std::time::SystemTime) -> String {
+  match time.duration_since(std::time::SystemTime::now()) {
+    Ok(until) => format!("in {}", format_duration(until)),
+    Err(e) => format!("{} ago", format_duration(e.duration())),
+  }
+}
+
+/// Find an executable on the PATH, like `which`
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+  let path = std::env::var_os("PATH")?;
+  std::env::split_paths(&path).map(|dir| dir.join(name)).find(|candidate| candidate.is_file())
+}
+
+/// Pick an editor: `$VISUAL`, then `$EDITOR`, then the first of a few common ones that's installed.
+/// Returns the program and any arguments (e.g. `EDITOR="code --wait"`), or `None` if nothing usable was found
+pub fn find_editor() -> Option<(String, Vec<String>)> {
+  let from_env = ["VISUAL", "EDITOR"].into_iter().filter_map(|var| std::env::var(var).ok());
+  let fallbacks = ["sensible-editor", "nano", "vi"].into_iter().map(String::from);
+
+  from_env.chain(fallbacks).find_map(|command| {
+    let mut parts = command.split_whitespace().map(String::from);
+    let program = parts.next()?;
+    let installed =
+      if program.contains('/') { Path::new(&program).is_file() } else { find_in_path(&program).is_some() };
+    installed.then(|| (program, parts.collect()))
+  })
+}
+
 pub fn get_data_dir() -> Result<PathBuf> {
   let directory = if let Ok(s) = std::env::var("SYSTEMCTL_TUI_DATA") {
     PathBuf::from(s)
@@ -79,7 +205,7 @@ pub fn initialize_logging(enable_tracing: bool) -> Result<WorkerGuard> {
   let tui_layer = tui_logger::tracing_subscriber_layer()
     .with_filter(EnvFilter::builder().with_default_directive(LevelFilter::INFO.into()).from_env_lossy());
 
-  tracing_subscriber::registry().with(file_layer).with(tui_layer).init();
+  tracing_subscriber::registry().with(file_layer).with(tui_layer).with(StatusMessageLayer).init();
 
   if enable_tracing {
     TRACING_ENABLED.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -154,6 +280,8 @@ pub fn version() -> String {
 
   let config_dir_path = get_config_dir().unwrap().display().to_string();
   let data_dir_path = get_data_dir().unwrap().display().to_string();
+  // the README's example lists every setting, a copy here would just go stale
+  let repository = env!("CARGO_PKG_REPOSITORY");
 
   format!(
     "\
@@ -162,6 +290,9 @@ pub fn version() -> String {
 Authors: {author}
 
 Config directory: {config_dir_path}
-Data directory: {data_dir_path}"
+Data directory: {data_dir_path}
+
+Settings are read from config.toml in the config directory (all optional).
+See {repository}#configuration for all of them"
   )
 }